@@ -3,11 +3,109 @@
 //! Comprehensive statistical analysis functions including descriptive statistics,
 //! trend analysis, correlation, and distribution analysis.
 
-use crate::{Statistics, TrendResult};
+use crate::{ConfidenceInterval, Statistics, TrendResult};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// Calculate comprehensive statistics for a data set
+/// Default number of bootstrap resamples used when deriving confidence
+/// intervals, matching common practice for percentile bootstraps.
+pub const DEFAULT_BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Fixed seed used when `calculate_trend_with_ci` bootstraps a slope CI,
+/// so repeated calls on the same series are reproducible.
+const DEFAULT_TREND_BOOTSTRAP_SEED: u64 = 0x5eed_0000_5eed_0000;
+
+fn zero_confidence_interval() -> ConfidenceInterval {
+    ConfidenceInterval {
+        point_estimate: 0.0,
+        lower: 0.0,
+        upper: 0.0,
+        confidence: 0.0,
+    }
+}
+
+/// Minimal splitmix64 PRNG so bootstrap resampling and Monte Carlo
+/// simulation are reproducible from a caller-supplied seed without
+/// depending on the `rand` crate.
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Return an index into `0..len`, uniformly distributed.
+    pub(crate) fn gen_index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Return a uniform value in `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Draw a standard normal sample via the Box-Muller transform.
+    pub(crate) fn next_gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+/// How non-finite (`NaN`/`inf`) values should be handled before analysis
+/// runs. Grades derived from division or missing data can produce these,
+/// and sorting them with a partial-order comparator panics, so callers that
+/// care must opt into an explicit policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NanPolicy {
+    /// Let non-finite values flow through unchanged; downstream statistics
+    /// may themselves come out as `NaN`.
+    Propagate,
+    /// Filter non-finite values out before any analysis runs.
+    Skip,
+    /// Refuse to analyze data containing non-finite values.
+    Error,
+}
+
+/// Calculate comprehensive statistics for a data set, first applying `policy`
+/// to any non-finite values. Ordering throughout uses `f64::total_cmp`, so
+/// unlike the plain `partial_cmp`-based sort this never panics.
+pub fn calculate_statistics_with(data: &[f64], policy: NanPolicy) -> Result<Statistics, String> {
+    let skipped;
+    let data = match policy {
+        NanPolicy::Propagate => data,
+        NanPolicy::Skip => {
+            skipped = data.iter().copied().filter(|x| x.is_finite()).collect::<Vec<f64>>();
+            &skipped
+        }
+        NanPolicy::Error => {
+            if let Some((index, value)) = data.iter().enumerate().find(|(_, x)| !x.is_finite()) {
+                return Err(format!("non-finite value {value} at index {index}"));
+            }
+            data
+        }
+    };
+
+    Ok(calculate_statistics_core(data))
+}
+
+/// Calculate comprehensive statistics for a data set, skipping any
+/// non-finite values (`NanPolicy::Skip`). Use `calculate_statistics_with`
+/// for other policies.
 pub fn calculate_statistics(data: &[f64]) -> Statistics {
+    calculate_statistics_with(data, NanPolicy::Skip)
+        .expect("Skip policy never returns an error")
+}
+
+fn calculate_statistics_core(data: &[f64]) -> Statistics {
     if data.is_empty() {
         return Statistics {
             count: 0,
@@ -31,12 +129,12 @@ pub fn calculate_statistics(data: &[f64]) -> Statistics {
     }
 
     let count = data.len();
-    let sum: f64 = data.iter().sum();
+    let sum = compensated_sum(data);
     let mean = sum / count as f64;
     
     let mut sorted = data.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    
+    sorted.sort_by(f64::total_cmp);
+
     let median = calculate_median(&sorted);
     let mode = calculate_mode(data);
     
@@ -83,7 +181,30 @@ pub fn calculate_mean(data: &[f64]) -> f64 {
     if data.is_empty() {
         return 0.0;
     }
-    data.iter().sum::<f64>() / data.len() as f64
+    compensated_sum(data) / data.len() as f64
+}
+
+/// Sum a slice using Neumaier's (improved Kahan) compensated summation.
+///
+/// A naive running sum loses precision on long series or when values have
+/// very different magnitudes (e.g. raw millisecond timestamps alongside
+/// grade values). This keeps a running compensation term `c` for the error
+/// lost at each step and folds it back in at the end.
+pub fn compensated_sum(data: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    let mut c = 0.0;
+
+    for &x in data {
+        let t = sum + x;
+        if sum.abs() >= x.abs() {
+            c += (sum - t) + x;
+        } else {
+            c += (x - t) + sum;
+        }
+        sum = t;
+    }
+
+    sum + c
 }
 
 /// Calculate the median of a sorted data set
@@ -133,8 +254,8 @@ pub fn calculate_variance(data: &[f64], mean: f64) -> f64 {
         return 0.0;
     }
     
-    let sum_squared_diff: f64 = data.iter().map(|x| (x - mean).powi(2)).sum();
-    sum_squared_diff / (data.len() - 1) as f64 // Sample variance (n-1)
+    let squared_diffs: Vec<f64> = data.iter().map(|x| (x - mean).powi(2)).collect();
+    compensated_sum(&squared_diffs) / (data.len() - 1) as f64 // Sample variance (n-1)
 }
 
 /// Calculate standard deviation
@@ -143,15 +264,17 @@ pub fn calculate_std_deviation(data: &[f64]) -> f64 {
     calculate_variance(data, mean).sqrt()
 }
 
-/// Calculate a specific percentile
+/// Calculate a specific percentile, skipping non-finite values
+/// (`NanPolicy::Skip`).
 pub fn calculate_percentile(data: &[f64], percentile: f64) -> f64 {
-    if data.is_empty() {
+    let finite: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+    if finite.is_empty() {
         return 0.0;
     }
-    
-    let mut sorted = data.to_vec();
-    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    
+
+    let mut sorted = finite;
+    sorted.sort_by(f64::total_cmp);
+
     calculate_percentile_sorted(&sorted, percentile)
 }
 
@@ -202,7 +325,10 @@ pub fn calculate_kurtosis(data: &[f64], mean: f64, std_dev: f64) -> f64 {
     (numerator / denominator) - adjustment
 }
 
-/// Calculate trend from time series data using linear regression
+/// Calculate trend from time series data using linear regression. Cheap
+/// O(n) fit with `slope_ci` left as a zero-width placeholder — call
+/// `calculate_trend_with_ci` instead if the caller actually surfaces the
+/// confidence interval, since that bootstraps and costs far more.
 pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
     if data.len() < 2 {
         return TrendResult {
@@ -212,6 +338,7 @@ pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
             direction: "stable".to_string(),
             strength: "none".to_string(),
             predicted_values: vec![],
+            slope_ci: zero_confidence_interval(),
         };
     }
     
@@ -224,14 +351,18 @@ pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
     
     let n = normalized.len() as f64;
     
-    let sum_x: f64 = normalized.iter().map(|(x, _)| x).sum();
-    let sum_y: f64 = normalized.iter().map(|(_, y)| y).sum();
-    let sum_xy: f64 = normalized.iter().map(|(x, y)| x * y).sum();
-    let sum_x2: f64 = normalized.iter().map(|(x, _)| x * x).sum();
-    let sum_y2: f64 = normalized.iter().map(|(_, y)| y * y).sum();
-    
+    let xs: Vec<f64> = normalized.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<f64> = normalized.iter().map(|(_, y)| *y).collect();
+    let xys: Vec<f64> = normalized.iter().map(|(x, y)| x * y).collect();
+    let x2s: Vec<f64> = normalized.iter().map(|(x, _)| x * x).collect();
+
+    let sum_x = compensated_sum(&xs);
+    let sum_y = compensated_sum(&ys);
+    let sum_xy = compensated_sum(&xys);
+    let sum_x2 = compensated_sum(&x2s);
+
     let denominator = n * sum_x2 - sum_x * sum_x;
-    
+
     if denominator.abs() < 1e-10 {
         return TrendResult {
             slope: 0.0,
@@ -240,6 +371,7 @@ pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
             direction: "stable".to_string(),
             strength: "none".to_string(),
             predicted_values: vec![],
+            slope_ci: zero_confidence_interval(),
         };
     }
     
@@ -284,7 +416,9 @@ pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
         .iter()
         .map(|(x, _)| slope * x + intercept)
         .collect();
-    
+
+    let slope_ci = zero_confidence_interval();
+
     TrendResult {
         slope,
         intercept,
@@ -292,9 +426,23 @@ pub fn calculate_trend(data: &[(i64, f64)]) -> TrendResult {
         direction: direction.to_string(),
         strength: strength.to_string(),
         predicted_values,
+        slope_ci,
     }
 }
 
+/// Like `calculate_trend`, but also bootstraps a confidence interval on the
+/// slope via `bootstrap_slope_ci`, using a fixed seed so repeated calls on
+/// the same series return the same bounds. Reserve this for call sites
+/// that actually surface the CI (the standalone trend export, top-level
+/// analytics) rather than internal hot loops like backtesting or gradient
+/// descent, since the bootstrap is `DEFAULT_BOOTSTRAP_RESAMPLES` times the
+/// cost of `calculate_trend`.
+pub fn calculate_trend_with_ci(data: &[(i64, f64)]) -> TrendResult {
+    let mut trend = calculate_trend(data);
+    trend.slope_ci = bootstrap_slope_ci(data, 0.95, DEFAULT_BOOTSTRAP_RESAMPLES, DEFAULT_TREND_BOOTSTRAP_SEED);
+    trend
+}
+
 /// Calculate Pearson correlation coefficient between two data sets
 pub fn calculate_correlation(data1: &[f64], data2: &[f64]) -> f64 {
     if data1.len() != data2.len() || data1.len() < 2 {
@@ -305,18 +453,16 @@ pub fn calculate_correlation(data1: &[f64], data2: &[f64]) -> f64 {
     let mean1 = calculate_mean(data1);
     let mean2 = calculate_mean(data2);
     
-    let mut sum_product = 0.0;
-    let mut sum_sq1 = 0.0;
-    let mut sum_sq2 = 0.0;
-    
-    for i in 0..data1.len() {
-        let diff1 = data1[i] - mean1;
-        let diff2 = data2[i] - mean2;
-        sum_product += diff1 * diff2;
-        sum_sq1 += diff1 * diff1;
-        sum_sq2 += diff2 * diff2;
-    }
-    
+    let products: Vec<f64> = (0..data1.len())
+        .map(|i| (data1[i] - mean1) * (data2[i] - mean2))
+        .collect();
+    let squares1: Vec<f64> = data1.iter().map(|x| (x - mean1).powi(2)).collect();
+    let squares2: Vec<f64> = data2.iter().map(|x| (x - mean2).powi(2)).collect();
+
+    let sum_product = compensated_sum(&products);
+    let sum_sq1 = compensated_sum(&squares1);
+    let sum_sq2 = compensated_sum(&squares2);
+
     let denominator = (sum_sq1 * sum_sq2).sqrt();
     
     if denominator.abs() < 1e-10 {
@@ -451,6 +597,140 @@ pub fn generate_histogram(data: &[f64], num_buckets: usize) -> Vec<(f64, f64, us
     buckets
 }
 
+/// Drop values outside `[p25 - 1.5*iqr, p75 + 1.5*iqr]`, reusing the same
+/// IQR bound `detect_outliers` uses. Intended as an opt-in preprocessing
+/// step before bucketing a `LogHistogram`, so a handful of anomalies don't
+/// stretch the useful range into a single bin.
+pub fn reject_outliers(data: &[f64]) -> Vec<f64> {
+    if data.len() < 4 {
+        return data.to_vec();
+    }
+
+    let stats = calculate_statistics(data);
+    let lower_bound = stats.percentile_25 - 1.5 * stats.iqr;
+    let upper_bound = stats.percentile_75 + 1.5 * stats.iqr;
+
+    data.iter()
+        .copied()
+        .filter(|&v| v >= lower_bound && v <= upper_bound)
+        .collect()
+}
+
+/// Streaming logarithmic histogram over positive values.
+///
+/// Each bucket spans a fixed multiplicative factor `1 + relative_precision`,
+/// so resolution stays proportional across the whole range instead of the
+/// fixed linear buckets `generate_histogram` uses, which lose resolution
+/// once a few extreme values stretch the range. Only bucket counts are
+/// retained (no raw samples), `merge` combines two histograms bucket-wise,
+/// and `quantile` answers approximate quantile queries in O(buckets).
+/// Values that are not strictly positive can't be log-bucketed and are
+/// tallied separately in `non_positive_count`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogHistogram {
+    relative_precision: f64,
+    base: f64,
+    buckets: HashMap<i64, usize>,
+    non_positive_count: usize,
+    count: usize,
+}
+
+impl LogHistogram {
+    /// Create a histogram whose buckets each span a `1 + relative_precision`
+    /// multiplicative factor (e.g. `0.05` for roughly 5% relative
+    /// resolution).
+    pub fn new(relative_precision: f64) -> Self {
+        let relative_precision = relative_precision.max(1e-6);
+        Self {
+            relative_precision,
+            base: 1.0 + relative_precision,
+            buckets: HashMap::new(),
+            non_positive_count: 0,
+            count: 0,
+        }
+    }
+
+    /// Build a histogram from a batch of values, optionally running
+    /// `reject_outliers` first so a few extreme values don't stretch the
+    /// bucket range and starve the rest of the distribution of resolution.
+    pub fn from_values(data: &[f64], relative_precision: f64, exclude_outliers: bool) -> Self {
+        let mut hist = Self::new(relative_precision);
+        let values = if exclude_outliers {
+            reject_outliers(data)
+        } else {
+            data.to_vec()
+        };
+        for value in values {
+            hist.push(value);
+        }
+        hist
+    }
+
+    /// Ingest a single value. Non-finite values are dropped; non-positive
+    /// finite values are counted but can't be log-bucketed.
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        self.count += 1;
+        if value <= 0.0 {
+            self.non_positive_count += 1;
+            return;
+        }
+        let index = (value.ln() / self.base.ln()).floor() as i64;
+        *self.buckets.entry(index).or_insert(0) += 1;
+    }
+
+    /// Combine another histogram's bucket counts into this one. Both
+    /// histograms must share the same `relative_precision`.
+    pub fn merge(&mut self, other: &LogHistogram) {
+        for (&index, &bucket_count) in &other.buckets {
+            *self.buckets.entry(index).or_insert(0) += bucket_count;
+        }
+        self.non_positive_count += other.non_positive_count;
+        self.count += other.count;
+    }
+
+    fn bucket_lower(&self, index: i64) -> f64 {
+        self.base.powi(index as i32)
+    }
+
+    /// Total number of values ingested (including non-positive ones).
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Approximate the value at `percentile` (0..100) from bucket
+    /// boundaries, without retaining raw samples.
+    pub fn quantile(&self, percentile: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let percentile = percentile.clamp(0.0, 100.0);
+        let target = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as usize;
+
+        if self.non_positive_count >= target {
+            return 0.0;
+        }
+        let mut seen = self.non_positive_count;
+
+        let mut indices: Vec<i64> = self.buckets.keys().copied().collect();
+        indices.sort_unstable();
+
+        let mut last_index = None;
+        for index in indices {
+            seen += self.buckets[&index];
+            last_index = Some(index);
+            if seen >= target {
+                return self.bucket_lower(index);
+            }
+        }
+
+        last_index.map(|index| self.bucket_lower(index)).unwrap_or(0.0)
+    }
+}
+
 /// Calculate autocorrelation for lag detection
 pub fn calculate_autocorrelation(data: &[f64], lag: usize) -> f64 {
     if data.len() <= lag {
@@ -472,6 +752,481 @@ pub fn calculate_autocorrelation(data: &[f64], lag: usize) -> f64 {
     sum / (n as f64 * variance)
 }
 
+/// Calculate the mean after dropping the lowest and highest `proportion`
+/// fraction of sorted values, so a handful of grade-entry outliers don't
+/// skew the average the way a plain mean would.
+pub fn calculate_trimmed_mean(data: &[f64], proportion: f64) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let proportion = proportion.clamp(0.0, 0.5);
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let trim_count = (sorted.len() as f64 * proportion).floor() as usize;
+    if trim_count * 2 >= sorted.len() {
+        return calculate_median(&sorted);
+    }
+
+    calculate_mean(&sorted[trim_count..sorted.len() - trim_count])
+}
+
+/// Calculate the mean after clamping (rather than dropping) the lowest and
+/// highest `proportion` fraction of sorted values to their boundary value.
+pub fn calculate_winsorized_mean(data: &[f64], proportion: f64) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let proportion = proportion.clamp(0.0, 0.5);
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let trim_count = (sorted.len() as f64 * proportion).floor() as usize;
+    if trim_count == 0 {
+        return calculate_mean(&sorted);
+    }
+    if trim_count * 2 >= sorted.len() {
+        return calculate_median(&sorted);
+    }
+
+    let lower_bound = sorted[trim_count];
+    let upper_bound = sorted[sorted.len() - 1 - trim_count];
+    let winsorized: Vec<f64> = sorted
+        .iter()
+        .map(|&x| x.clamp(lower_bound, upper_bound))
+        .collect();
+
+    calculate_mean(&winsorized)
+}
+
+/// Calculate the median absolute deviation: the median of `|x_i - median|`.
+pub fn calculate_mad(data: &[f64]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let median = calculate_median(&sorted);
+
+    let mut deviations: Vec<f64> = data.iter().map(|x| (x - median).abs()).collect();
+    deviations.sort_by(f64::total_cmp);
+
+    calculate_median(&deviations)
+}
+
+/// MAD scaled by 1.4826, a robust estimator of the standard deviation that
+/// is consistent for normally-distributed data but far less sensitive to
+/// outliers than `calculate_std_deviation`.
+pub fn calculate_mad_scaled(data: &[f64]) -> f64 {
+    calculate_mad(data) * 1.4826
+}
+
+/// Approximate the standard normal inverse CDF (quantile function) using
+/// Acklam's rational approximation (accurate to about 1.15e-9), so the
+/// confidence-interval helpers below don't need a statistics crate.
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.383577518672690e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+
+    let p = p.clamp(1e-10, 1.0 - 1e-10);
+    let p_low = 0.02425;
+    let p_high = 1.0 - p_low;
+
+    if p < p_low {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= p_high {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    }
+}
+
+/// Approximate the Student's-t quantile for `df` degrees of freedom using a
+/// Cornish-Fisher expansion from the normal quantile. There's no hard
+/// dependency on a stats crate, and the approximation is accurate enough for
+/// the small sample sizes a single student's grade history produces.
+pub fn t_quantile(p: f64, df: f64) -> f64 {
+    let z = normal_quantile(p);
+    if df <= 0.0 {
+        return z;
+    }
+
+    let z2 = z * z;
+    let z3 = z2 * z;
+    let z5 = z3 * z2;
+    let z7 = z5 * z2;
+    let z9 = z7 * z2;
+
+    let g1 = (z3 + z) / 4.0;
+    let g2 = (5.0 * z5 + 16.0 * z3 + 3.0 * z) / 96.0;
+    let g3 = (3.0 * z7 + 19.0 * z5 + 17.0 * z3 - 15.0 * z) / 384.0;
+    let g4 = (79.0 * z9 + 776.0 * z7 + 1482.0 * z5 - 1920.0 * z3 - 945.0 * z) / 92160.0;
+
+    z + g1 / df + g2 / df.powi(2) + g3 / df.powi(3) + g4 / df.powi(4)
+}
+
+/// Estimate the mean together with a confidence interval that accounts for
+/// autocorrelation in the series, returning `(mean, lower, upper)`.
+///
+/// Consecutive grades in a term are correlated over time, so the naive
+/// `std/sqrt(n)` standard error understates uncertainty. This instead
+/// estimates the long-run variance from a Bartlett-tapered sum of
+/// autocovariances out to lag `K ≈ 0.5 * sqrt(n)`, shrinks the effective
+/// sample size accordingly, and builds the interval from a Student's-t
+/// quantile at that effective size.
+pub fn mean_confidence_interval(data: &[f64], confidence: f64) -> (f64, f64, f64) {
+    let (mean, _std_error, lower, upper) = mean_confidence_interval_with_bandwidth(data, confidence, 0.5);
+    (mean, lower, upper)
+}
+
+/// Like `mean_confidence_interval`, but also returns the autocorrelation-
+/// corrected standard error, and with a caller-supplied bandwidth
+/// coefficient controlling how many lags (`K ≈ bandwidth_coeff * sqrt(n)`)
+/// contribute to the long-run variance estimate. Returns
+/// `(mean, std_error, lower, upper)`.
+pub fn mean_confidence_interval_with_bandwidth(
+    data: &[f64],
+    confidence: f64,
+    bandwidth_coeff: f64,
+) -> (f64, f64, f64, f64) {
+    let n = data.len();
+    if n == 0 {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    let mean = calculate_mean(data);
+    if n < 2 {
+        return (mean, 0.0, mean, mean);
+    }
+
+    let gamma_0 = calculate_variance(data, mean);
+    if gamma_0 == 0.0 {
+        return (mean, 0.0, mean, mean);
+    }
+
+    let max_lag = ((bandwidth_coeff * (n as f64).sqrt()).round() as usize)
+        .max(1)
+        .min(n - 1);
+
+    let mut lrv = gamma_0;
+    for k in 1..=max_lag {
+        let gamma_k = calculate_autocorrelation(data, k) * gamma_0;
+        let w_k = 1.0 - (k as f64) / (max_lag as f64 + 1.0);
+        lrv += 2.0 * w_k * gamma_k;
+    }
+    lrv = lrv.max(1e-12);
+
+    let n_eff = (n as f64 * gamma_0 / lrv).clamp(1.0, n as f64);
+    let std_error = (lrv / n as f64).sqrt();
+
+    let alpha = (1.0 - confidence).clamp(0.0, 1.0);
+    let t = t_quantile(1.0 - alpha / 2.0, (n_eff - 1.0).max(1.0));
+    let half_width = t * std_error;
+
+    (mean, std_error, mean - half_width, mean + half_width)
+}
+
+/// Draw `resamples` bootstrap samples (same size, with replacement) from
+/// `data`, apply `stat_fn` to each, and return the observed statistic plus
+/// the percentile bounds for `confidence` (e.g. 0.95 -> 2.5%/97.5%).
+///
+/// The PRNG is seeded explicitly so results are reproducible across runs.
+pub fn bootstrap_statistic<F>(
+    data: &[f64],
+    stat_fn: F,
+    resamples: usize,
+    confidence: f64,
+    seed: u64,
+) -> ConfidenceInterval
+where
+    F: Fn(&[f64]) -> f64,
+{
+    if data.is_empty() {
+        return zero_confidence_interval();
+    }
+
+    let point_estimate = stat_fn(data);
+
+    if resamples == 0 {
+        return ConfidenceInterval {
+            point_estimate,
+            lower: point_estimate,
+            upper: point_estimate,
+            confidence,
+        };
+    }
+
+    let mut rng = Rng::new(seed);
+    let mut resample_buffer = vec![0.0; data.len()];
+    let mut distribution: Vec<f64> = Vec::with_capacity(resamples);
+
+    for _ in 0..resamples {
+        for slot in resample_buffer.iter_mut() {
+            *slot = data[rng.gen_index(data.len())];
+        }
+        distribution.push(stat_fn(&resample_buffer));
+    }
+
+    distribution.sort_by(f64::total_cmp);
+
+    let alpha = (1.0 - confidence).clamp(0.0, 1.0);
+    let lower = calculate_percentile_sorted(&distribution, (alpha / 2.0) * 100.0);
+    let upper = calculate_percentile_sorted(&distribution, (1.0 - alpha / 2.0) * 100.0);
+
+    ConfidenceInterval {
+        point_estimate,
+        lower,
+        upper,
+        confidence,
+    }
+}
+
+/// Bootstrap a confidence interval for the slope of `calculate_trend` by
+/// resampling `(time, value)` pairs with replacement and refitting the line.
+pub fn bootstrap_slope_ci(
+    data: &[(i64, f64)],
+    confidence: f64,
+    resamples: usize,
+    seed: u64,
+) -> ConfidenceInterval {
+    if data.len() < 2 {
+        return zero_confidence_interval();
+    }
+
+    let point_estimate = calculate_trend_slope_only(data);
+
+    let mut rng = Rng::new(seed);
+    let mut resample_buffer = vec![(0i64, 0.0); data.len()];
+    let mut distribution: Vec<f64> = Vec::with_capacity(resamples);
+
+    for _ in 0..resamples {
+        for slot in resample_buffer.iter_mut() {
+            *slot = data[rng.gen_index(data.len())];
+        }
+        distribution.push(calculate_trend_slope_only(&resample_buffer));
+    }
+
+    distribution.sort_by(f64::total_cmp);
+
+    let alpha = (1.0 - confidence).clamp(0.0, 1.0);
+    let lower = calculate_percentile_sorted(&distribution, (alpha / 2.0) * 100.0);
+    let upper = calculate_percentile_sorted(&distribution, (1.0 - alpha / 2.0) * 100.0);
+
+    ConfidenceInterval {
+        point_estimate,
+        lower,
+        upper,
+        confidence,
+    }
+}
+
+/// Fit a line to `(time, value)` pairs and return only the slope, skipping
+/// the rest of `TrendResult` so each bootstrap resample stays O(n) instead
+/// of reconstructing direction/strength/predicted_values it won't use.
+fn calculate_trend_slope_only(data: &[(i64, f64)]) -> f64 {
+    let min_time = data.iter().map(|(t, _)| *t).min().unwrap_or(0);
+    let normalized: Vec<(f64, f64)> = data
+        .iter()
+        .map(|(t, v)| ((t - min_time) as f64, *v))
+        .collect();
+
+    let n = normalized.len() as f64;
+    let xs: Vec<f64> = normalized.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<f64> = normalized.iter().map(|(_, y)| *y).collect();
+    let xys: Vec<f64> = normalized.iter().map(|(x, y)| x * y).collect();
+    let x2s: Vec<f64> = normalized.iter().map(|(x, _)| x * x).collect();
+
+    let sum_x = compensated_sum(&xs);
+    let sum_y = compensated_sum(&ys);
+    let sum_xy = compensated_sum(&xys);
+    let sum_x2 = compensated_sum(&x2s);
+
+    let denominator = n * sum_x2 - sum_x * sum_x;
+    if denominator.abs() < 1e-10 {
+        return 0.0;
+    }
+
+    (n * sum_xy - sum_x * sum_y) / denominator
+}
+
+/// Incrementally maintains count, mean, and central moments (M2/M3/M4) using
+/// Welford's online algorithm, so mean/variance/skewness/kurtosis are all
+/// available in O(1) per sample without retaining the raw data.
+///
+/// Two accumulators built from disjoint batches can be folded together with
+/// `merge`, using the Chan et al. parallel-variance formulas.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsAccumulator {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+}
+
+impl StatsAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold a single new value into the running moments.
+    pub fn push(&mut self, x: f64) {
+        let n_prev = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term1 = delta * delta_n * n_prev;
+
+        self.mean += delta_n;
+        self.m4 += term1 * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term1 * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term1;
+    }
+
+    /// Combine with another accumulator built from a disjoint batch of values.
+    pub fn merge(&mut self, other: &StatsAccumulator) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        let n_a = self.count as f64;
+        let n_b = other.count as f64;
+        let n = n_a + n_b;
+
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let delta3 = delta2 * delta;
+        let delta4 = delta2 * delta2;
+
+        let mean = self.mean + delta * n_b / n;
+        let m2 = self.m2 + other.m2 + delta2 * n_a * n_b / n;
+        let m3 = self.m3
+            + other.m3
+            + delta3 * n_a * n_b * (n_a - n_b) / (n * n)
+            + 3.0 * delta * (n_a * other.m2 - n_b * self.m2) / n;
+        let m4 = self.m4
+            + other.m4
+            + delta4 * n_a * n_b * (n_a * n_a - n_a * n_b + n_b * n_b) / (n * n * n)
+            + 6.0 * delta2 * (n_a * n_a * other.m2 + n_b * n_b * self.m2) / (n * n)
+            + 4.0 * delta * (n_a * other.m3 - n_b * self.m3) / n;
+
+        self.count = n as usize;
+        self.mean = mean;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    pub fn skewness(&self) -> f64 {
+        if self.count < 3 || self.m2 == 0.0 {
+            return 0.0;
+        }
+        let n = self.count as f64;
+        (n.sqrt() * self.m3) / self.m2.powf(1.5)
+    }
+
+    pub fn kurtosis(&self) -> f64 {
+        if self.count < 4 || self.m2 == 0.0 {
+            return 0.0;
+        }
+        let n = self.count as f64;
+        (n * self.m4) / (self.m2 * self.m2) - 3.0
+    }
+
+    /// Produce a point-in-time `Statistics` snapshot from the running moments.
+    ///
+    /// Fields that require the raw samples (median, mode, percentiles, min/max)
+    /// cannot be derived from moments alone and are left at their zero value.
+    pub fn snapshot(&self) -> Statistics {
+        Statistics {
+            count: self.count,
+            sum: self.mean * self.count as f64,
+            mean: self.mean,
+            median: 0.0,
+            mode: vec![],
+            min: 0.0,
+            max: 0.0,
+            range: 0.0,
+            variance: self.variance(),
+            std_deviation: self.variance().sqrt(),
+            percentile_25: 0.0,
+            percentile_50: 0.0,
+            percentile_75: 0.0,
+            percentile_90: 0.0,
+            iqr: 0.0,
+            skewness: self.skewness(),
+            kurtosis: self.kurtosis(),
+        }
+    }
+
+    /// Alias for `snapshot`, for callers that think of this as draining an
+    /// unbounded stream into a final result rather than peeking at one.
+    pub fn finalize(&self) -> Statistics {
+        self.snapshot()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -515,4 +1270,206 @@ mod tests {
         assert!(trend.direction == "improving");
         assert!((trend.slope - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_stats_accumulator_matches_batch() {
+        let data = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let mut acc = StatsAccumulator::new();
+        for &x in &data {
+            acc.push(x);
+        }
+
+        let batch = calculate_statistics(&data);
+        assert!((acc.mean() - batch.mean).abs() < 1e-9);
+        assert!((acc.variance() - batch.variance).abs() < 1e-9);
+        assert_eq!(acc.finalize().count, acc.snapshot().count);
+    }
+
+    #[test]
+    fn test_trimmed_mean_drops_outliers() {
+        let data = vec![1.0, 5.0, 6.0, 7.0, 100.0];
+        let trimmed = calculate_trimmed_mean(&data, 0.2);
+        assert!((trimmed - 6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_winsorized_mean_clamps_outliers() {
+        let data = vec![1.0, 5.0, 6.0, 7.0, 100.0];
+        let winsorized = calculate_winsorized_mean(&data, 0.2);
+        // 1.0 -> 5.0, 100.0 -> 7.0: mean(5,5,6,7,7) = 6.0
+        assert!((winsorized - 6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mad() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        // median = 3.0, deviations = [2,1,0,1,2] -> median = 1.0
+        assert!((calculate_mad(&data) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_compensated_sum_matches_naive_sum_on_well_conditioned_data() {
+        let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert!((compensated_sum(&data) - 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bootstrap_statistic_mean_ci_contains_point_estimate() {
+        let data = vec![5.0, 6.0, 6.5, 7.0, 7.5, 8.0, 8.5];
+        let ci = bootstrap_statistic(&data, calculate_mean, 500, 0.95, 42);
+
+        assert!((ci.point_estimate - calculate_mean(&data)).abs() < 1e-9);
+        assert!(ci.lower <= ci.point_estimate && ci.point_estimate <= ci.upper);
+    }
+
+    #[test]
+    fn test_bootstrap_statistic_is_reproducible_for_same_seed() {
+        let data = vec![1.0, 3.0, 2.0, 9.0, 4.0];
+        let a = bootstrap_statistic(&data, calculate_mean, 200, 0.95, 7);
+        let b = bootstrap_statistic(&data, calculate_mean, 200, 0.95, 7);
+
+        assert_eq!(a.lower, b.lower);
+        assert_eq!(a.upper, b.upper);
+    }
+
+    #[test]
+    fn test_stats_accumulator_merge() {
+        let data = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let (left, right) = data.split_at(3);
+
+        let mut a = StatsAccumulator::new();
+        for &x in left {
+            a.push(x);
+        }
+        let mut b = StatsAccumulator::new();
+        for &x in right {
+            b.push(x);
+        }
+        a.merge(&b);
+
+        let mut whole = StatsAccumulator::new();
+        for &x in &data {
+            whole.push(x);
+        }
+
+        assert!((a.mean() - whole.mean()).abs() < 1e-9);
+        assert!((a.variance() - whole.variance()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_t_quantile_approaches_normal_quantile_for_large_df() {
+        let t = t_quantile(0.975, 10_000.0);
+        assert!((t - 1.959964).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_mean_confidence_interval_contains_mean_and_widens_with_autocorrelation() {
+        let independent = vec![5.0, 6.0, 5.5, 6.5, 5.0, 6.0, 5.5, 6.5, 5.0, 6.0];
+        let (mean, lower, upper) = mean_confidence_interval(&independent, 0.95);
+        assert!((mean - calculate_mean(&independent)).abs() < 1e-9);
+        assert!(lower <= mean && mean <= upper);
+
+        let trending = vec![5.0, 5.1, 5.2, 5.3, 5.4, 5.5, 5.6, 5.7, 5.8, 5.9];
+        let (_, trending_lower, trending_upper) = mean_confidence_interval(&trending, 0.95);
+        let (_, iid_lower, iid_upper) = mean_confidence_interval(&independent, 0.95);
+        assert!((trending_upper - trending_lower) > (iid_upper - iid_lower));
+    }
+
+    #[test]
+    fn test_mean_confidence_interval_single_point_has_zero_width() {
+        let (mean, lower, upper) = mean_confidence_interval(&[7.0], 0.95);
+        assert_eq!(mean, 7.0);
+        assert_eq!(lower, 7.0);
+        assert_eq!(upper, 7.0);
+    }
+
+    #[test]
+    fn test_calculate_statistics_with_skip_filters_non_finite() {
+        let data = vec![6.0, f64::NAN, 7.0, f64::INFINITY, 8.0];
+        let stats = calculate_statistics_with(&data, NanPolicy::Skip).unwrap();
+        assert_eq!(stats.count, 3);
+        assert!((stats.mean - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_calculate_statistics_with_error_reports_index() {
+        let data = vec![6.0, f64::NAN, 7.0];
+        let err = calculate_statistics_with(&data, NanPolicy::Error).unwrap_err();
+        assert!(err.contains("index 1"));
+    }
+
+    #[test]
+    fn test_calculate_statistics_with_propagate_does_not_panic() {
+        let data = vec![6.0, f64::NAN, 7.0];
+        let stats = calculate_statistics_with(&data, NanPolicy::Propagate).unwrap();
+        assert_eq!(stats.count, 3);
+    }
+
+    #[test]
+    fn test_calculate_statistics_default_is_skip() {
+        let data = vec![6.0, f64::NAN, 8.0];
+        let stats = calculate_statistics(&data);
+        assert_eq!(stats.count, 2);
+        assert!((stats.mean - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_log_histogram_quantile_approximates_median() {
+        let mut hist = LogHistogram::new(0.01);
+        for v in [5.0, 6.0, 6.5, 7.0, 7.5, 8.0, 9.0] {
+            hist.push(v);
+        }
+        assert_eq!(hist.count(), 7);
+        let median = hist.quantile(50.0);
+        assert!((median - 7.0).abs() / 7.0 < 0.05);
+    }
+
+    #[test]
+    fn test_log_histogram_merge_matches_combined_push() {
+        let mut a = LogHistogram::new(0.02);
+        let mut b = LogHistogram::new(0.02);
+        for v in [4.0, 5.0, 6.0] {
+            a.push(v);
+        }
+        for v in [7.0, 8.0, 9.0] {
+            b.push(v);
+        }
+        a.merge(&b);
+
+        let mut whole = LogHistogram::new(0.02);
+        for v in [4.0, 5.0, 6.0, 7.0, 8.0, 9.0] {
+            whole.push(v);
+        }
+
+        assert_eq!(a.count(), whole.count());
+        assert!((a.quantile(50.0) - whole.quantile(50.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_log_histogram_tracks_non_positive_separately() {
+        let mut hist = LogHistogram::new(0.05);
+        hist.push(-1.0);
+        hist.push(0.0);
+        hist.push(5.0);
+        assert_eq!(hist.count(), 3);
+        assert_eq!(hist.quantile(10.0), 0.0);
+    }
+
+    #[test]
+    fn test_reject_outliers_drops_extreme_values() {
+        let data = vec![6.0, 6.5, 7.0, 7.5, 8.0, 100.0];
+        let cleaned = reject_outliers(&data);
+        assert!(!cleaned.contains(&100.0));
+        assert!(cleaned.contains(&7.0));
+    }
+
+    #[test]
+    fn test_log_histogram_from_values_excludes_outliers() {
+        let data = vec![6.0, 6.5, 7.0, 7.5, 8.0, 100.0];
+        let with_outlier = LogHistogram::from_values(&data, 0.01, false);
+        let without_outlier = LogHistogram::from_values(&data, 0.01, true);
+        assert_eq!(with_outlier.count(), 6);
+        assert_eq!(without_outlier.count(), 5);
+        assert!(without_outlier.quantile(100.0) < with_outlier.quantile(100.0));
+    }
 }
@@ -0,0 +1,199 @@
+//! Baseline Comparison
+//!
+//! Diffs a current `AnalyticsResult` against a previously saved snapshot so
+//! the extension can highlight what has moved since a baseline was taken
+//! (e.g. the start of a term).
+
+use crate::{AnalyticsDelta, AnalyticsResult, MetricDelta, SubjectDelta, SubjectSummary};
+
+/// Default fraction of the baseline value a subject's average may move by
+/// before it is flagged as "improved" or "declined" rather than "stable".
+pub const STABLE_THRESHOLD: f64 = 0.02;
+
+/// Compare `current` against `baseline` and report the change in the
+/// headline metrics plus a per-subject breakdown.
+pub fn compare_to_baseline(
+    current: &AnalyticsResult,
+    baseline: &AnalyticsResult,
+    stable_threshold: f64,
+) -> AnalyticsDelta {
+    let overall_average = metric_delta(baseline.overall_average, current.overall_average);
+    let weighted_average = metric_delta(baseline.weighted_average, current.weighted_average);
+    let gpa = metric_delta(baseline.gpa, current.gpa);
+    let pass_rate = metric_delta(baseline.pass_rate, current.pass_rate);
+    let trend = metric_delta(baseline.trend.slope, current.trend.slope);
+
+    let subjects = compare_subjects(&current.subjects, &baseline.subjects, stable_threshold);
+    let status = classify_status(weighted_average.change, stable_threshold * weighted_average.baseline.max(1.0));
+
+    AnalyticsDelta {
+        overall_average,
+        weighted_average,
+        gpa,
+        pass_rate,
+        trend,
+        subjects,
+        status,
+    }
+}
+
+fn compare_subjects(
+    current: &[SubjectSummary],
+    baseline: &[SubjectSummary],
+    stable_threshold: f64,
+) -> Vec<SubjectDelta> {
+    current
+        .iter()
+        .map(|subject| {
+            let baseline_subject = baseline
+                .iter()
+                .find(|b| b.subject.to_lowercase() == subject.subject.to_lowercase());
+
+            let (baseline_avg, baseline_weighted, baseline_trend) = baseline_subject
+                .map(|b| (b.average, b.weighted_average, b.trend))
+                .unwrap_or((0.0, 0.0, 0.0));
+
+            let average = metric_delta(baseline_avg, subject.average);
+            let weighted_average = metric_delta(baseline_weighted, subject.weighted_average);
+            let trend = metric_delta(baseline_trend, subject.trend);
+
+            let threshold = stable_threshold * baseline_weighted.max(1.0);
+            let status = classify_status(weighted_average.change, threshold);
+
+            SubjectDelta {
+                subject: subject.subject.clone(),
+                average,
+                weighted_average,
+                trend,
+                status,
+            }
+        })
+        .collect()
+}
+
+fn metric_delta(baseline: f64, current: f64) -> MetricDelta {
+    let change = current - baseline;
+    let change_percent = if baseline.abs() > 1e-9 {
+        (change / baseline) * 100.0
+    } else {
+        0.0
+    };
+
+    MetricDelta {
+        baseline,
+        current,
+        change,
+        change_percent,
+    }
+}
+
+fn classify_status(change: f64, threshold: f64) -> String {
+    if change > threshold {
+        "improved".to_string()
+    } else if change < -threshold {
+        "declined".to_string()
+    } else {
+        "stable".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Statistics, TrendResult};
+
+    fn empty_result(subjects: Vec<SubjectSummary>, weighted_average: f64) -> AnalyticsResult {
+        AnalyticsResult {
+            overall_average: weighted_average,
+            weighted_average,
+            gpa: 0.0,
+            total_grades: subjects.len(),
+            passing_grades: 0,
+            failing_grades: 0,
+            pass_rate: 0.0,
+            subjects,
+            statistics: Statistics {
+                count: 0,
+                sum: 0.0,
+                mean: 0.0,
+                median: 0.0,
+                mode: vec![],
+                min: 0.0,
+                max: 0.0,
+                range: 0.0,
+                variance: 0.0,
+                std_deviation: 0.0,
+                percentile_25: 0.0,
+                percentile_50: 0.0,
+                percentile_75: 0.0,
+                percentile_90: 0.0,
+                iqr: 0.0,
+                skewness: 0.0,
+                kurtosis: 0.0,
+            },
+            trend: TrendResult {
+                slope: 0.0,
+                intercept: 0.0,
+                r_squared: 0.0,
+                direction: "stable".to_string(),
+                strength: "none".to_string(),
+                predicted_values: vec![],
+                slope_ci: crate::ConfidenceInterval {
+                    point_estimate: 0.0,
+                    lower: 0.0,
+                    upper: 0.0,
+                    confidence: 0.0,
+                },
+            },
+            predictions: vec![],
+            extra: std::collections::HashMap::new(),
+            anomalies: vec![],
+        }
+    }
+
+    fn subject(name: &str, weighted_average: f64) -> SubjectSummary {
+        SubjectSummary {
+            subject: name.to_string(),
+            average: weighted_average,
+            weighted_average,
+            grade_count: 1,
+            total_weight: 1.0,
+            highest: weighted_average,
+            lowest: weighted_average,
+            passing_count: 1,
+            failing_count: 0,
+            trend: 0.0,
+            predicted_next: weighted_average,
+            band_distribution: std::collections::HashMap::new(),
+            average_error: crate::MeanWithError {
+                mean: weighted_average,
+                std_error: 0.0,
+                ci_low: weighted_average,
+                ci_high: weighted_average,
+            },
+        }
+    }
+
+    #[test]
+    fn test_compare_to_baseline_flags_improvement() {
+        let baseline = empty_result(vec![subject("Math", 6.0)], 6.0);
+        let current = empty_result(vec![subject("Math", 7.5)], 7.5);
+
+        let delta = compare_to_baseline(&current, &baseline, STABLE_THRESHOLD);
+
+        assert_eq!(delta.status, "improved");
+        assert_eq!(delta.subjects[0].status, "improved");
+        assert!((delta.weighted_average.change - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compare_to_baseline_stable_within_threshold() {
+        let baseline = empty_result(vec![subject("Math", 7.0)], 7.0);
+        let current = empty_result(vec![subject("Math", 7.01)], 7.01);
+
+        let delta = compare_to_baseline(&current, &baseline, STABLE_THRESHOLD);
+
+        assert_eq!(delta.status, "stable");
+    }
+
+}
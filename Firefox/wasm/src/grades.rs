@@ -5,10 +5,129 @@
 
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
-use crate::{Grade, GpaScale, SubjectSummary, AnalyticsResult, Statistics, TrendResult, PredictionResult};
+use crate::{
+    Anomaly, Grade, GpaScale, MeanWithError, ScoreWeights, SubjectHealthScore, SubjectSummary,
+    AnalyticsResult, Statistics, TrendResult, PredictionResult,
+};
 use crate::statistics;
 use crate::predictions;
 
+/// A raw grade entry that failed to deserialize, kept alongside the reason
+/// so the extension can surface scrape errors without losing the good data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedGrade {
+    pub index: usize,
+    pub raw: serde_json::Value,
+    pub reason: String,
+}
+
+/// Result of leniently parsing a batch of scraped grade JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LenientParseResult {
+    pub grades: Vec<Grade>,
+    pub rejected: Vec<RejectedGrade>,
+}
+
+/// Parse a JSON array of grade-like records, keeping every record that
+/// deserializes successfully (tolerating Dutch-formatted number strings and
+/// a missing `weight`, per `Grade`'s `Deserialize` impl) and collecting the
+/// rest as rejected entries with their failure reason.
+pub fn parse_grades_lenient(grades_json: &str) -> Result<LenientParseResult, serde_json::Error> {
+    let raw_entries: Vec<serde_json::Value> = serde_json::from_str(grades_json)?;
+
+    let mut grades = Vec::new();
+    let mut rejected = Vec::new();
+
+    for (index, entry) in raw_entries.into_iter().enumerate() {
+        match serde_json::from_value::<Grade>(entry.clone()) {
+            Ok(grade) => grades.push(grade),
+            Err(e) => rejected.push(RejectedGrade {
+                index,
+                raw: entry,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(LenientParseResult { grades, rejected })
+}
+
+/// Streaming accumulator of grade values, weighted by `Grade::weight` using
+/// West's online weighted-variance algorithm, so the running mean matches
+/// `calculate_weighted_average` instead of `calculate_simple_average`.
+/// Grades with a non-positive weight are ignored, same as a zero-weight
+/// entry would be in a plain weighted sum. For the unweighted case, stream
+/// grade values into the wasm-exported `StatsAccumulator` directly (its
+/// `push_grade` takes a JSON-encoded `Grade` and folds in only its value).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WeightedIncrementalStats {
+    sum_weight: f64,
+    mean: f64,
+    m2: f64,
+}
+
+impl WeightedIncrementalStats {
+    pub fn new() -> Self {
+        Self { sum_weight: 0.0, mean: 0.0, m2: 0.0 }
+    }
+
+    /// Fold one more grade's `(value, weight)` pair into the running moments.
+    pub fn add(&mut self, grade: &Grade) {
+        if grade.weight <= 0.0 {
+            return;
+        }
+
+        let new_sum_weight = self.sum_weight + grade.weight;
+        let delta = grade.value - self.mean;
+        let step = delta * grade.weight / new_sum_weight;
+
+        self.mean += step;
+        self.m2 += self.sum_weight * delta * step;
+        self.sum_weight = new_sum_weight;
+    }
+
+    /// Combine with another accumulator built from a disjoint batch of grades.
+    pub fn merge(&mut self, other: &WeightedIncrementalStats) {
+        if other.sum_weight <= 0.0 {
+            return;
+        }
+        if self.sum_weight <= 0.0 {
+            *self = *other;
+            return;
+        }
+
+        let new_sum_weight = self.sum_weight + other.sum_weight;
+        let delta = other.mean - self.mean;
+
+        self.mean += delta * other.sum_weight / new_sum_weight;
+        self.m2 += other.m2 + delta * delta * self.sum_weight * other.sum_weight / new_sum_weight;
+        self.sum_weight = new_sum_weight;
+    }
+
+    pub fn sum_weight(&self) -> f64 {
+        self.sum_weight
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Weighted population variance.
+    pub fn variance(&self) -> f64 {
+        if self.sum_weight <= 0.0 {
+            0.0
+        } else {
+            self.m2 / self.sum_weight
+        }
+    }
+}
+
+impl Default for WeightedIncrementalStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Pass/fail statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PassFailStats {
@@ -46,6 +165,19 @@ pub fn calculate_weighted_average(grades: &[Grade]) -> f64 {
     weighted_sum / total_weight
 }
 
+/// Calculate the (unweighted) mean of `grades` together with an
+/// autocorrelation-corrected confidence interval, since consecutive grades
+/// in a subject tend to be correlated over time and a naive `std/sqrt(n)`
+/// understates the uncertainty. Delegates to
+/// `statistics::mean_confidence_interval_with_bandwidth` for the estimate.
+pub fn calculate_mean_with_error(grades: &[Grade], confidence: f64) -> MeanWithError {
+    let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
+    let (mean, std_error, ci_low, ci_high) =
+        statistics::mean_confidence_interval_with_bandwidth(&values, confidence, 0.5);
+
+    MeanWithError { mean, std_error, ci_low, ci_high }
+}
+
 /// Calculate GPA from grades using the specified scale
 pub fn calculate_gpa(grades: &[Grade], scale: &GpaScale) -> f64 {
     if grades.is_empty() {
@@ -101,6 +233,8 @@ pub fn get_subject_summary(grades: &[Grade], subject: &str) -> SubjectSummary {
             failing_count: 0,
             trend: 0.0,
             predicted_next: 0.0,
+            band_distribution: HashMap::new(),
+            average_error: MeanWithError { mean: 0.0, std_error: 0.0, ci_low: 0.0, ci_high: 0.0 },
         };
     }
     
@@ -139,7 +273,12 @@ pub fn get_subject_summary(grades: &[Grade], subject: &str) -> SubjectSummary {
     // Predict next grade
     let subject_grades_vec: Vec<Grade> = subject_grades.iter().map(|g| (*g).clone()).collect();
     let prediction = predictions::predict_next_grade(&subject_grades_vec);
-    
+
+    // Count how many grades fall into each qualitative rating band
+    let band_distribution = calculate_band_distribution(&values);
+
+    let average_error = calculate_mean_with_error(&subject_grades_vec, 0.95);
+
     SubjectSummary {
         subject: subject.to_string(),
         average,
@@ -152,9 +291,23 @@ pub fn get_subject_summary(grades: &[Grade], subject: &str) -> SubjectSummary {
         failing_count,
         trend,
         predicted_next: prediction.predicted_value,
+        band_distribution,
+        average_error,
     }
 }
 
+/// Count how many values fall into each qualitative `GradeBand`
+fn calculate_band_distribution(values: &[f64]) -> HashMap<String, usize> {
+    let mut distribution: HashMap<String, usize> = HashMap::new();
+
+    for &value in values {
+        let band = crate::GradeBand::from_value(value);
+        *distribution.entry(band.as_str().to_string()).or_default() += 1;
+    }
+
+    distribution
+}
+
 /// Get summaries for all subjects
 pub fn get_all_subject_summaries(grades: &[Grade]) -> Vec<SubjectSummary> {
     let mut subjects: HashMap<String, Vec<&Grade>> = HashMap::new();
@@ -224,8 +377,74 @@ pub fn calculate_pass_fail_stats(grades: &[Grade]) -> PassFailStats {
     }
 }
 
-/// Perform comprehensive analysis on all grades
+/// A named aggregation over a grade slice, registered into an
+/// `AggregationRegistry` and folded into `AnalyticsResult::extra` by
+/// `analyze_all`, so downstream users can add domain-specific metrics
+/// (e.g. "credits-weighted GPA", "percent above 8") without forking the crate.
+pub struct AggregationRegistry {
+    scorers: Vec<(String, Box<dyn Fn(&[Grade]) -> f64>)>,
+}
+
+impl AggregationRegistry {
+    /// An empty registry with no scorers; use `register` to add some, or
+    /// start from `AggregationRegistry::default()` for the built-in set.
+    pub fn new() -> Self {
+        Self { scorers: Vec::new() }
+    }
+
+    /// Register a named aggregation, overriding any existing one with the
+    /// same name.
+    pub fn register(&mut self, name: &str, scorer: impl Fn(&[Grade]) -> f64 + 'static) {
+        if let Some(entry) = self.scorers.iter_mut().find(|(existing, _)| existing == name) {
+            entry.1 = Box::new(scorer);
+        } else {
+            self.scorers.push((name.to_string(), Box::new(scorer)));
+        }
+    }
+
+    fn evaluate(&self, grades: &[Grade]) -> HashMap<String, f64> {
+        self.scorers
+            .iter()
+            .map(|(name, scorer)| (name.clone(), scorer(grades)))
+            .collect()
+    }
+}
+
+impl Default for AggregationRegistry {
+    /// Ships a few built-in scorers: credits-weighted GPA, median absolute
+    /// deviation, and the percentage of grades at or above 8.0. Callers can
+    /// override or extend this set before calling `analyze_all_with_registry`.
+    fn default() -> Self {
+        let mut registry = Self::new();
+
+        registry.register("credits_weighted_gpa", |grades| {
+            calculate_gpa(grades, &GpaScale::default())
+        });
+        registry.register("median_absolute_deviation", |grades| {
+            let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
+            statistics::calculate_mad(&values)
+        });
+        registry.register("percent_above_8", |grades| {
+            if grades.is_empty() {
+                return 0.0;
+            }
+            let above = grades.iter().filter(|g| g.value >= 8.0).count();
+            (above as f64 / grades.len() as f64) * 100.0
+        });
+
+        registry
+    }
+}
+
+/// Perform comprehensive analysis on all grades, using the default
+/// `AggregationRegistry` for `AnalyticsResult::extra`.
 pub fn analyze_all(grades: &[Grade]) -> AnalyticsResult {
+    analyze_all_with_registry(grades, &AggregationRegistry::default())
+}
+
+/// Like `analyze_all`, but folding `registry`'s named aggregations into
+/// `AnalyticsResult::extra` instead of the default scorer set.
+pub fn analyze_all_with_registry(grades: &[Grade], registry: &AggregationRegistry) -> AnalyticsResult {
     if grades.is_empty() {
         return AnalyticsResult {
             overall_average: 0.0,
@@ -262,11 +481,19 @@ pub fn analyze_all(grades: &[Grade]) -> AnalyticsResult {
                 direction: "stable".to_string(),
                 strength: "none".to_string(),
                 predicted_values: vec![],
+                slope_ci: crate::ConfidenceInterval {
+                    point_estimate: 0.0,
+                    lower: 0.0,
+                    upper: 0.0,
+                    confidence: 0.0,
+                },
             },
             predictions: vec![],
+            extra: registry.evaluate(grades),
+            anomalies: vec![],
         };
     }
-    
+
     let overall_average = calculate_simple_average(grades);
     let weighted_average = calculate_weighted_average(grades);
     let gpa = calculate_gpa(grades, &GpaScale::default());
@@ -284,8 +511,8 @@ pub fn analyze_all(grades: &[Grade]) -> AnalyticsResult {
         .map(|g| (g.timestamp, g.value))
         .collect();
     time_series.sort_by_key(|(t, _)| *t);
-    let trend = statistics::calculate_trend(&time_series);
-    
+    let trend = statistics::calculate_trend_with_ci(&time_series);
+
     // Generate predictions for each subject
     let predictions: Vec<PredictionResult> = subjects
         .iter()
@@ -298,7 +525,9 @@ pub fn analyze_all(grades: &[Grade]) -> AnalyticsResult {
             predictions::predict_next_grade(&subject_grades)
         })
         .collect();
-    
+
+    let anomalies = detect_anomalies(grades);
+
     AnalyticsResult {
         overall_average,
         weighted_average,
@@ -311,6 +540,8 @@ pub fn analyze_all(grades: &[Grade]) -> AnalyticsResult {
         statistics: stats,
         trend,
         predictions,
+        extra: registry.evaluate(grades),
+        anomalies,
     }
 }
 
@@ -367,6 +598,184 @@ fn chrono_lite_month_key(timestamp_secs: i64) -> String {
     format!("{:04}-{:02}", year, month.min(12))
 }
 
+/// Fixed number of points every anomaly window is resampled to. A small
+/// fixed length keeps the naive DFT cheap and makes frequency bins comparable across
+/// subjects regardless of how many grades they happen to have.
+const ANOMALY_WINDOW_LEN: usize = 64;
+
+/// Number of low-frequency FFT magnitude bins kept as anomaly features.
+const ANOMALY_FFT_BINS: usize = 16;
+
+/// Minimum number of grades a subject needs before it gets anomaly windows;
+/// below this a window is too short for its frequency content to mean
+/// anything.
+const ANOMALY_MIN_SUBJECT_POINTS: usize = 5;
+
+/// Default z-scored Euclidean distance above which a window is flagged.
+pub const DEFAULT_ANOMALY_THRESHOLD: f64 = 2.5;
+
+/// Resample a chronologically-sorted `(timestamp, value)` series to
+/// `len` evenly time-spaced points via linear interpolation.
+///
+/// Core invariant: grade timestamps are not evenly spaced (a student can
+/// get three tests in a week, then nothing for a month), but the FFT below
+/// assumes a uniform sample rate. Every window MUST pass through this
+/// resampling step before its frequency content is touched, or the bin
+/// magnitudes measure gaps in reporting rather than patterns in grades.
+fn resample_fixed_length(series: &[(i64, f64)], len: usize) -> Vec<f64> {
+    if series.len() == 1 {
+        return vec![series[0].1; len];
+    }
+
+    let start = series.first().unwrap().0 as f64;
+    let end = series.last().unwrap().0 as f64;
+    let span = (end - start).max(1.0);
+
+    (0..len)
+        .map(|i| {
+            let t = start + span * (i as f64) / ((len - 1) as f64);
+            interpolate_at(series, t)
+        })
+        .collect()
+}
+
+/// Linearly interpolate the value at time `t` within `series`, clamping to
+/// the first/last observed value outside the series' own time range.
+fn interpolate_at(series: &[(i64, f64)], t: f64) -> f64 {
+    if t <= series[0].0 as f64 {
+        return series[0].1;
+    }
+    if t >= series[series.len() - 1].0 as f64 {
+        return series[series.len() - 1].1;
+    }
+
+    for pair in series.windows(2) {
+        let (t0, v0) = (pair[0].0 as f64, pair[0].1);
+        let (t1, v1) = (pair[1].0 as f64, pair[1].1);
+        if t >= t0 && t <= t1 {
+            let frac = if (t1 - t0).abs() < 1e-9 { 0.0 } else { (t - t0) / (t1 - t0) };
+            return v0 + frac * (v1 - v0);
+        }
+    }
+
+    series.last().unwrap().1
+}
+
+/// Naive discrete Fourier transform magnitudes for the first `num_bins`
+/// frequencies. `values` is always `ANOMALY_WINDOW_LEN` long here, so the
+/// O(n * num_bins) cost stays small without pulling in an FFT crate.
+fn anomaly_fft_magnitudes(values: &[f64], num_bins: usize) -> Vec<f64> {
+    let n = values.len();
+    (0..num_bins.min(n))
+        .map(|k| {
+            let mut re = 0.0;
+            let mut im = 0.0;
+            for (t, &value) in values.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (k as f64) * (t as f64) / (n as f64);
+                re += value * angle.cos();
+                im += value * angle.sin();
+            }
+            (re * re + im * im).sqrt()
+        })
+        .collect()
+}
+
+/// Build a window's feature vector: the first `ANOMALY_FFT_BINS` FFT
+/// magnitudes followed by mean, std, min and max of the resampled window.
+fn anomaly_features(window: &[f64]) -> Vec<f64> {
+    let mean = window.iter().sum::<f64>() / window.len() as f64;
+    let variance = window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window.len() as f64;
+    let std = variance.sqrt();
+    let min = window.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = window.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    let mut features = anomaly_fft_magnitudes(window, ANOMALY_FFT_BINS);
+    features.push(mean);
+    features.push(std);
+    features.push(min);
+    features.push(max);
+    features
+}
+
+/// Flag grades whose resampled, FFT-featurized window looks unusual next
+/// to the rest of its subject's history, using the default threshold.
+pub fn detect_anomalies(grades: &[Grade]) -> Vec<Anomaly> {
+    detect_anomalies_with_threshold(grades, DEFAULT_ANOMALY_THRESHOLD)
+}
+
+/// Flag grades whose resampled, FFT-featurized window scores above
+/// `threshold` in z-scored Euclidean distance from its subject's windows
+/// as a whole. Useful for catching data-entry errors or unusually
+/// out-of-character results.
+///
+/// For each subject, every grade gets a window spanning from that
+/// subject's earliest grade up to (and including) itself, resampled per
+/// `resample_fixed_length`. A reference distribution (per-feature mean and
+/// std) is built from all of a subject's windows, the scored one included, and each window is
+/// scored by its z-scored Euclidean distance to that reference.
+pub fn detect_anomalies_with_threshold(grades: &[Grade], threshold: f64) -> Vec<Anomaly> {
+    let mut by_subject: HashMap<String, Vec<&Grade>> = HashMap::new();
+    for grade in grades {
+        by_subject.entry(grade.subject.to_lowercase()).or_default().push(grade);
+    }
+
+    let mut anomalies = Vec::new();
+
+    for (subject, subject_grades) in by_subject {
+        let mut sorted = subject_grades;
+        sorted.sort_by_key(|g| g.timestamp);
+
+        if sorted.len() < ANOMALY_MIN_SUBJECT_POINTS {
+            continue;
+        }
+
+        let series: Vec<(i64, f64)> = sorted.iter().map(|g| (g.timestamp, g.value)).collect();
+
+        let feature_vectors: Vec<Vec<f64>> = (ANOMALY_MIN_SUBJECT_POINTS - 1..series.len())
+            .map(|end| anomaly_features(&resample_fixed_length(&series[..=end], ANOMALY_WINDOW_LEN)))
+            .collect();
+
+        let num_features = feature_vectors[0].len();
+        let mut feature_means = vec![0.0; num_features];
+        let mut feature_stds = vec![0.0; num_features];
+        for f in 0..num_features {
+            let column: Vec<f64> = feature_vectors.iter().map(|v| v[f]).collect();
+            let mean = column.iter().sum::<f64>() / column.len() as f64;
+            let variance = column.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / column.len() as f64;
+            feature_means[f] = mean;
+            feature_stds[f] = variance.sqrt();
+        }
+
+        for (offset, features) in feature_vectors.iter().enumerate() {
+            let score = features
+                .iter()
+                .enumerate()
+                .map(|(f, value)| {
+                    if feature_stds[f] > 1e-9 {
+                        ((value - feature_means[f]) / feature_stds[f]).powi(2)
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f64>()
+                .sqrt();
+
+            if score > threshold {
+                let grade = sorted[offset + ANOMALY_MIN_SUBJECT_POINTS - 1];
+                anomalies.push(Anomaly {
+                    subject: subject.clone(),
+                    timestamp: grade.timestamp,
+                    value: grade.value,
+                    score,
+                });
+            }
+        }
+    }
+
+    anomalies.sort_by(|a, b| b.score.total_cmp(&a.score));
+    anomalies
+}
+
 /// Calculate grade distribution (histogram)
 pub fn calculate_distribution(grades: &[Grade]) -> HashMap<String, usize> {
     let mut distribution: HashMap<String, usize> = HashMap::new();
@@ -427,13 +836,74 @@ pub fn calculate_improvement(grades: &[Grade]) -> f64 {
     last_avg - first_avg
 }
 
-/// Get grades that need attention (failing or close to failing)
+/// Normalize a 1-10 grade value into `[0, 1]`.
+fn normalize_grade_value(value: f64) -> f64 {
+    ((value - 1.0) / 9.0).clamp(0.0, 1.0)
+}
+
+/// Map a trend slope into `[0, 1]`, clamping to +/-1 grade point per timestamp unit
+/// before rescaling so a wildly steep trend doesn't dominate the blend.
+fn normalize_trend(trend: f64) -> f64 {
+    (trend.clamp(-1.0, 1.0) + 1.0) / 2.0
+}
+
+/// Default health-score cutoff below which `get_attention_needed` flags a
+/// subject as needing attention.
+const DEFAULT_ATTENTION_CUTOFF: f64 = 0.55;
+
+/// Blend each subject's weighted average, pass rate, trend, and predicted
+/// next grade (each normalized into `[0, 1]`) into a single health score
+/// using `weights`, so apps can rank subjects consistently instead of
+/// relying on hard-coded thresholds. Sorted lowest (least healthy) first.
+pub fn calculate_subject_health_scores(grades: &[Grade], weights: &ScoreWeights) -> Vec<SubjectHealthScore> {
+    let mut scores: Vec<SubjectHealthScore> = get_all_subject_summaries(grades)
+        .into_iter()
+        .map(|s| {
+            let pass_rate = if s.grade_count > 0 {
+                s.passing_count as f64 / s.grade_count as f64
+            } else {
+                0.0
+            };
+
+            let score = weights.average * normalize_grade_value(s.weighted_average)
+                + weights.pass_rate * pass_rate
+                + weights.trend * normalize_trend(s.trend)
+                + weights.predicted_next * normalize_grade_value(s.predicted_next);
+
+            SubjectHealthScore {
+                subject: s.subject,
+                score,
+                weighted_average: s.weighted_average,
+                pass_rate,
+                trend: s.trend,
+                predicted_next: s.predicted_next,
+            }
+        })
+        .collect();
+
+    scores.sort_by(|a, b| a.score.total_cmp(&b.score));
+    scores
+}
+
+/// Get subjects that need attention, using the default health-score cutoff.
 pub fn get_attention_needed(grades: &[Grade]) -> Vec<SubjectSummary> {
-    let summaries = get_all_subject_summaries(grades);
-    
-    summaries
+    get_attention_needed_with_cutoff(grades, DEFAULT_ATTENTION_CUTOFF)
+}
+
+/// Like `get_attention_needed`, with a caller-chosen cutoff: subjects
+/// whose blended `SubjectHealthScore` (using the default `ScoreWeights`)
+/// falls below `cutoff` are flagged, replacing the old hard-coded
+/// `weighted_average < 6.0 || trend < -0.1` rule with a single tunable number.
+pub fn get_attention_needed_with_cutoff(grades: &[Grade], cutoff: f64) -> Vec<SubjectSummary> {
+    let flagged: std::collections::HashSet<String> = calculate_subject_health_scores(grades, &ScoreWeights::default())
+        .into_iter()
+        .filter(|s| s.score < cutoff)
+        .map(|s| s.subject)
+        .collect();
+
+    get_all_subject_summaries(grades)
         .into_iter()
-        .filter(|s| s.weighted_average < 6.0 || s.trend < -0.1)
+        .filter(|s| flagged.contains(&s.subject))
         .collect()
 }
 
@@ -473,6 +943,41 @@ mod tests {
         assert!((math_avg - 7.333).abs() < 0.01);
     }
 
+    #[test]
+    fn test_subject_summary_includes_mean_error() {
+        let grades = create_test_grades();
+        let math_grades: Vec<Grade> = grades
+            .iter()
+            .filter(|g| g.subject == "Math")
+            .cloned()
+            .collect();
+
+        let summary = get_subject_summary(&grades, "Math");
+        let expected = calculate_mean_with_error(&math_grades, 0.95);
+
+        assert!((summary.average_error.mean - expected.mean).abs() < 1e-9);
+        assert!((summary.average_error.ci_low - expected.ci_low).abs() < 1e-9);
+        assert!((summary.average_error.ci_high - expected.ci_high).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_grades_lenient_accepts_dutch_strings_and_missing_weight() {
+        let json = r#"[
+            {"value": "7,5", "subject": "Math", "description": "Test 1", "timestamp": 1000, "is_passing": true},
+            {"value": 8.0, "weight": "2,0", "subject": "Math", "description": "Test 2", "timestamp": 2000, "is_passing": true},
+            {"subject": "Math", "description": "Missing value", "timestamp": 3000, "is_passing": false}
+        ]"#;
+
+        let result = parse_grades_lenient(json).unwrap();
+
+        assert_eq!(result.grades.len(), 2);
+        assert!((result.grades[0].value - 7.5).abs() < 1e-9);
+        assert!((result.grades[0].weight - 1.0).abs() < 1e-9);
+        assert!((result.grades[1].weight - 2.0).abs() < 1e-9);
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].index, 2);
+    }
+
     #[test]
     fn test_pass_fail_stats() {
         let grades = create_test_grades();
@@ -481,4 +986,152 @@ mod tests {
         assert_eq!(stats.passing, 4);
         assert_eq!(stats.failing, 0);
     }
+
+    #[test]
+    fn test_weighted_incremental_stats_matches_calculate_weighted_average() {
+        let grades = create_test_grades();
+        let mut acc = WeightedIncrementalStats::new();
+        for grade in &grades {
+            acc.add(grade);
+        }
+
+        assert!((acc.mean() - calculate_weighted_average(&grades)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mean_with_error_contains_simple_average() {
+        let grades = create_test_grades();
+        let result = calculate_mean_with_error(&grades, 0.95);
+
+        assert!((result.mean - calculate_simple_average(&grades)).abs() < 1e-9);
+        assert!(result.ci_low <= result.mean && result.mean <= result.ci_high);
+    }
+
+    #[test]
+    fn test_mean_with_error_single_grade_has_zero_width() {
+        let grades = vec![Grade::new(8.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000)];
+        let result = calculate_mean_with_error(&grades, 0.95);
+
+        assert_eq!(result.ci_low, result.mean);
+        assert_eq!(result.ci_high, result.mean);
+    }
+
+    #[test]
+    fn test_subject_health_scores_rank_struggling_subject_lowest() {
+        let grades = vec![
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 2".to_string(), 2000),
+            Grade::new(3.0, 1.0, "Gym".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(4.0, 1.0, "Gym".to_string(), "Test 2".to_string(), 2000),
+        ];
+
+        let scores = calculate_subject_health_scores(&grades, &ScoreWeights::default());
+
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].subject, "gym");
+        assert!(scores[0].score < scores[1].score);
+    }
+
+    #[test]
+    fn test_get_attention_needed_with_cutoff_flags_low_scoring_subject() {
+        let grades = vec![
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(3.0, 1.0, "Gym".to_string(), "Test 1".to_string(), 1000),
+        ];
+
+        let flagged = get_attention_needed_with_cutoff(&grades, 0.5);
+
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].subject, "gym");
+    }
+
+    #[test]
+    fn test_analyze_all_includes_default_registry_metrics() {
+        let grades = create_test_grades();
+        let result = analyze_all(&grades);
+
+        assert!(result.extra.contains_key("credits_weighted_gpa"));
+        assert!(result.extra.contains_key("median_absolute_deviation"));
+        assert!(result.extra.contains_key("percent_above_8"));
+    }
+
+    #[test]
+    fn test_aggregation_registry_runs_custom_scorer() {
+        let grades = create_test_grades();
+        let mut registry = AggregationRegistry::new();
+        registry.register("grade_count", |grades| grades.len() as f64);
+
+        let result = analyze_all_with_registry(&grades, &registry);
+
+        assert_eq!(result.extra.len(), 1);
+        assert_eq!(result.extra["grade_count"], grades.len() as f64);
+    }
+
+    #[test]
+    fn test_aggregation_registry_register_overrides_existing_name() {
+        let grades = create_test_grades();
+        let mut registry = AggregationRegistry::new();
+        registry.register("always_one", |_| 1.0);
+        registry.register("always_one", |_| 2.0);
+
+        let result = analyze_all_with_registry(&grades, &registry);
+
+        assert_eq!(result.extra["always_one"], 2.0);
+    }
+
+    #[test]
+    fn test_analyze_all_includes_detected_anomalies() {
+        let mut grades: Vec<Grade> = (0..10)
+            .map(|i| Grade::new(7.0, 1.0, "Math".to_string(), format!("Test {}", i), i * 86_400_000))
+            .collect();
+        grades[7].value = 1.0;
+
+        let result = analyze_all(&grades);
+
+        assert_eq!(result.anomalies, detect_anomalies(&grades));
+    }
+
+    #[test]
+    fn test_detect_anomalies_flags_outlier_grade_in_otherwise_stable_subject() {
+        let mut grades: Vec<Grade> = (0..10)
+            .map(|i| Grade::new(7.0, 1.0, "Math".to_string(), format!("Test {}", i), i * 86_400_000))
+            .collect();
+        grades[7].value = 1.0;
+
+        let anomalies = detect_anomalies(&grades);
+
+        assert!(anomalies.iter().any(|a| a.subject == "math" && a.value == 1.0));
+    }
+
+    #[test]
+    fn test_detect_anomalies_ignores_subjects_below_minimum_points() {
+        let grades = vec![
+            Grade::new(9.0, 1.0, "Gym".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(2.0, 1.0, "Gym".to_string(), "Test 2".to_string(), 2000),
+        ];
+
+        assert!(detect_anomalies(&grades).is_empty());
+    }
+
+    #[test]
+    fn test_detect_anomalies_with_threshold_respects_stricter_cutoff() {
+        let mut grades: Vec<Grade> = (0..10)
+            .map(|i| Grade::new(7.0, 1.0, "Math".to_string(), format!("Test {}", i), i * 86_400_000))
+            .collect();
+        grades[7].value = 1.0;
+
+        assert!(detect_anomalies_with_threshold(&grades, 1000.0).is_empty());
+    }
+
+    #[test]
+    fn test_resample_fixed_length_interpolates_between_irregular_points() {
+        let series = vec![(0_i64, 0.0), (1000_i64, 10.0)];
+
+        let resampled = resample_fixed_length(&series, 3);
+
+        assert_eq!(resampled.len(), 3);
+        assert!((resampled[0] - 0.0).abs() < 1e-9);
+        assert!((resampled[1] - 5.0).abs() < 1e-9);
+        assert!((resampled[2] - 10.0).abs() < 1e-9);
+    }
 }
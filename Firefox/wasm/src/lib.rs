@@ -6,9 +6,13 @@
 mod grades;
 mod statistics;
 mod predictions;
+mod comparison;
 
 use wasm_bindgen::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 // Initialize panic hook for better error messages in browser console
 #[wasm_bindgen(start)]
@@ -22,9 +26,16 @@ pub fn init() {
 // ============================================================================
 
 /// Represents a single grade entry
+///
+/// `value` and `weight` accept either a JSON number or a Dutch-formatted
+/// decimal string ("7,5"), since grades are scraped from a live browser DOM
+/// and frequently arrive in either shape. `weight` also defaults to `1.0`
+/// when the scraped record omits it entirely.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Grade {
+    #[serde(deserialize_with = "deserialize_flexible_number")]
     pub value: f64,
+    #[serde(default = "default_weight", deserialize_with = "deserialize_flexible_weight")]
     pub weight: f64,
     pub subject: String,
     pub description: String,
@@ -32,6 +43,46 @@ pub struct Grade {
     pub is_passing: bool,
 }
 
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// A JSON value that may be either a number or a Dutch-formatted decimal string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrDutchString {
+    Number(f64),
+    Text(String),
+}
+
+fn deserialize_flexible_number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match NumberOrDutchString::deserialize(deserializer)? {
+        NumberOrDutchString::Number(n) => Ok(n),
+        NumberOrDutchString::Text(s) => parse_dutch_number(&s)
+            .map_err(|e| serde::de::Error::custom(format!("invalid value '{}': {}", s, e))),
+    }
+}
+
+fn deserialize_flexible_weight<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<NumberOrDutchString>::deserialize(deserializer)? {
+        Some(NumberOrDutchString::Number(n)) => Ok(n),
+        Some(NumberOrDutchString::Text(s)) => parse_dutch_number(&s)
+            .map_err(|e| serde::de::Error::custom(format!("invalid weight '{}': {}", s, e))),
+        None => Ok(default_weight()),
+    }
+}
+
+/// Parse a Dutch-formatted ("7,5") or plain ("7.5") decimal string
+fn parse_dutch_number(s: &str) -> Result<f64, std::num::ParseFloatError> {
+    s.trim().replace(',', ".").parse::<f64>()
+}
+
 impl Grade {
     pub fn new(value: f64, weight: f64, subject: String, description: String, timestamp: i64) -> Self {
         Self {
@@ -59,6 +110,146 @@ pub struct SubjectSummary {
     pub failing_count: usize,
     pub trend: f64,
     pub predicted_next: f64,
+    pub band_distribution: HashMap<String, usize>,
+    /// 95%-confidence autocorrelation-corrected error bar on `average`, from
+    /// `grades::calculate_mean_with_error`. `average` and `average_error.mean`
+    /// are the same value; this is here so callers get the uncertainty
+    /// without a second pass over the subject's grades.
+    pub average_error: MeanWithError,
+}
+
+/// Weights for blending a subject's normalized signals into a single
+/// `SubjectHealthScore`. Should sum to 1.0 for the score to stay in `[0, 1]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreWeights {
+    pub average: f64,
+    pub pass_rate: f64,
+    pub trend: f64,
+    pub predicted_next: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            average: 0.35,
+            pass_rate: 0.25,
+            trend: 0.15,
+            predicted_next: 0.25,
+        }
+    }
+}
+
+/// A subject's blended health score, plus the normalized-into-`[0, 1]`
+/// components it was built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectHealthScore {
+    pub subject: String,
+    pub score: f64,
+    pub weighted_average: f64,
+    pub pass_rate: f64,
+    pub trend: f64,
+    pub predicted_next: f64,
+}
+
+/// A grade whose resampled time-series window scored as unusual against
+/// the rest of its subject's history, per an FFT-featurized anomaly scan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub subject: String,
+    pub timestamp: i64,
+    pub value: f64,
+    pub score: f64,
+}
+
+/// Qualitative Dutch grade rating, bucketed from a numeric 1-10 score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GradeBand {
+    Onvoldoende,
+    Zwak,
+    Voldoende,
+    RuimVoldoende,
+    Goed,
+    Uitstekend,
+}
+
+impl GradeBand {
+    /// Classify a grade value using the default Dutch thresholds.
+    pub fn from_value(value: f64) -> Self {
+        Self::from_value_with_scale(value, &GradeBandScale::default())
+    }
+
+    /// Classify a grade value using caller-supplied thresholds.
+    pub fn from_value_with_scale(value: f64, scale: &GradeBandScale) -> Self {
+        if value < scale.zwak {
+            GradeBand::Onvoldoende
+        } else if value < scale.voldoende {
+            GradeBand::Zwak
+        } else if value < scale.ruim_voldoende {
+            GradeBand::Voldoende
+        } else if value < scale.goed {
+            GradeBand::RuimVoldoende
+        } else if value < scale.uitstekend {
+            GradeBand::Goed
+        } else {
+            GradeBand::Uitstekend
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GradeBand::Onvoldoende => "Onvoldoende",
+            GradeBand::Zwak => "Zwak",
+            GradeBand::Voldoende => "Voldoende",
+            GradeBand::RuimVoldoende => "Ruim voldoende",
+            GradeBand::Goed => "Goed",
+            GradeBand::Uitstekend => "Uitstekend",
+        }
+    }
+}
+
+impl fmt::Display for GradeBand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for GradeBand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "onvoldoende" => Ok(GradeBand::Onvoldoende),
+            "zwak" => Ok(GradeBand::Zwak),
+            "voldoende" => Ok(GradeBand::Voldoende),
+            "ruim voldoende" | "ruimvoldoende" => Ok(GradeBand::RuimVoldoende),
+            "goed" => Ok(GradeBand::Goed),
+            "uitstekend" => Ok(GradeBand::Uitstekend),
+            other => Err(format!("Unknown grade band: {}", other)),
+        }
+    }
+}
+
+/// Threshold configuration for classifying grades into a `GradeBand`,
+/// following the same per-scale configuration pattern as `GpaScale`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradeBandScale {
+    pub zwak: f64,
+    pub voldoende: f64,
+    pub ruim_voldoende: f64,
+    pub goed: f64,
+    pub uitstekend: f64,
+}
+
+impl Default for GradeBandScale {
+    fn default() -> Self {
+        Self {
+            zwak: 5.5,
+            voldoende: 6.0,
+            ruim_voldoende: 7.0,
+            goed: 8.0,
+            uitstekend: 9.0,
+        }
+    }
 }
 
 /// Complete statistics result
@@ -83,6 +274,25 @@ pub struct Statistics {
     pub kurtosis: f64,
 }
 
+/// A point estimate with bootstrap-derived lower/upper confidence bounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub point_estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub confidence: f64,
+}
+
+/// A mean together with its autocorrelation-corrected standard error and
+/// confidence interval — see `statistics::mean_confidence_interval_with_bandwidth`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeanWithError {
+    pub mean: f64,
+    pub std_error: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
 /// Trend analysis result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrendResult {
@@ -92,6 +302,7 @@ pub struct TrendResult {
     pub direction: String,
     pub strength: String,
     pub predicted_values: Vec<f64>,
+    pub slope_ci: ConfidenceInterval,
 }
 
 /// Prediction result with confidence intervals
@@ -132,6 +343,77 @@ pub struct ImpactEntry {
     pub impact: f64,
 }
 
+/// Per-method accuracy report from walk-forward backtesting, with the
+/// resulting ensemble weights `predict_next_grade` derives from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub folds: usize,
+    pub trend_mae: f64,
+    pub trend_rmse: f64,
+    pub trend_weight: f64,
+    pub ema_mae: f64,
+    pub ema_rmse: f64,
+    pub ema_weight: f64,
+    pub regression_mae: f64,
+    pub regression_rmse: f64,
+    pub regression_weight: f64,
+    pub isotonic_mae: f64,
+    pub isotonic_rmse: f64,
+    pub isotonic_weight: f64,
+}
+
+/// Configuration for the finite-difference gradient descent that calibrates
+/// per-student prediction parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientDescentConfig {
+    pub learning_rate: f64,
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for GradientDescentConfig {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.05,
+            tolerance: 1e-4,
+            max_iterations: 50,
+        }
+    }
+}
+
+/// Prediction parameters fitted to a student's history by gradient descent,
+/// plus the loss curve showing convergence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibratedParameters {
+    pub alpha: f64,
+    pub regression_exponent: f64,
+    pub loss_curve: Vec<f64>,
+    pub iterations: usize,
+}
+
+/// Monte Carlo estimate of pass probability, with a Wilson score confidence
+/// interval on the simulated pass fraction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassProbabilityResult {
+    pub pass_fraction: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    pub trials: usize,
+}
+
+/// A subject's FSRS-style memory state: stability (days until
+/// retrievability decays to 0.9), difficulty, the
+/// retrievability estimated right now, and the timestamp at which it's
+/// next expected to cross the target retention threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSchedule {
+    pub subject: String,
+    pub stability: f64,
+    pub difficulty: f64,
+    pub current_retrievability: f64,
+    pub next_review_timestamp: i64,
+}
+
 /// GPA scale configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpaScale {
@@ -164,6 +446,51 @@ pub struct AnalyticsResult {
     pub statistics: Statistics,
     pub trend: TrendResult,
     pub predictions: Vec<PredictionResult>,
+    /// Named metrics from the `AggregationRegistry` used to build this
+    /// result (credits-weighted GPA, MAD, etc. by default), so downstream
+    /// users can add domain-specific metrics without forking the crate.
+    pub extra: HashMap<String, f64>,
+    /// Grades flagged by `grades::detect_anomalies` as statistical outliers
+    /// within their own subject.
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// A single tracked metric's change between a baseline and the current state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub baseline: f64,
+    pub current: f64,
+    pub change: f64,
+    pub change_percent: f64,
+}
+
+/// Per-subject comparison against a baseline snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubjectDelta {
+    pub subject: String,
+    pub average: MetricDelta,
+    pub weighted_average: MetricDelta,
+    pub trend: MetricDelta,
+    pub status: String,
+}
+
+/// Full comparison of an `AnalyticsResult` against a saved baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsDelta {
+    pub overall_average: MetricDelta,
+    pub weighted_average: MetricDelta,
+    pub gpa: MetricDelta,
+    pub pass_rate: MetricDelta,
+    pub trend: MetricDelta,
+    pub subjects: Vec<SubjectDelta>,
+    pub status: String,
+}
+
+/// `Statistics` paired with a bootstrap confidence interval on the mean
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsWithCi {
+    pub statistics: Statistics,
+    pub mean_ci: ConfidenceInterval,
 }
 
 // ============================================================================
@@ -230,13 +557,58 @@ pub fn get_subject_summary(grades_json: &str, subject: &str) -> Result<String, J
 pub fn get_all_subjects(grades_json: &str) -> Result<String, JsValue> {
     let grades: Vec<Grade> = serde_json::from_str(grades_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
-    
+
     let subjects = grades::get_all_subject_summaries(&grades);
-    
+
+    serde_json::to_string(&subjects)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize subjects: {}", e)))
+}
+
+/// Blend each subject's average, pass rate, trend and predicted next grade
+/// into a single tunable health score, using the default `ScoreWeights`
+#[wasm_bindgen]
+pub fn calculate_subject_health_scores(grades_json: &str) -> Result<String, JsValue> {
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let scores = grades::calculate_subject_health_scores(&grades, &ScoreWeights::default());
+
+    serde_json::to_string(&scores)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize scores: {}", e)))
+}
+
+/// Get subjects whose health score falls below the default cutoff
+#[wasm_bindgen]
+pub fn get_attention_needed(grades_json: &str) -> Result<String, JsValue> {
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let subjects = grades::get_attention_needed(&grades);
+
     serde_json::to_string(&subjects)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize subjects: {}", e)))
 }
 
+// The `binary` feature and its `rmp-serde` dependency are declared in the
+// Cargo.toml that packages this crate for the extension build, which lives
+// outside this checkout (this tree is a `src/`-only snapshot with no
+// manifest for any of its dependencies, not just this one). Nothing here
+// should assume a manifest is present in this directory.
+
+/// Get all subjects with their summaries, reading and writing MessagePack
+/// instead of JSON.
+#[cfg(feature = "binary")]
+#[wasm_bindgen]
+pub fn get_all_subjects_bin(grades: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let grades: Vec<Grade> = rmp_serde::from_slice(grades)
+        .map_err(|e| JsValue::from_str(&format!("Failed to decode grades: {}", e)))?;
+
+    let subjects = grades::get_all_subject_summaries(&grades);
+
+    rmp_serde::to_vec(&subjects)
+        .map_err(|e| JsValue::from_str(&format!("Failed to encode subjects: {}", e)))
+}
+
 /// Calculate pass/fail statistics
 #[wasm_bindgen]
 pub fn calculate_pass_fail_stats(grades_json: &str) -> Result<String, JsValue> {
@@ -254,13 +626,43 @@ pub fn calculate_pass_fail_stats(grades_json: &str) -> Result<String, JsValue> {
 pub fn analyze_all_grades(grades_json: &str) -> Result<String, JsValue> {
     let grades: Vec<Grade> = serde_json::from_str(grades_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
-    
+
     let result = grades::analyze_all(&grades);
-    
+
     serde_json::to_string(&result)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
 }
 
+/// Perform complete analytics on all grades, reading and writing MessagePack
+/// instead of JSON. Meaningfully smaller and faster to decode for bulk grade
+/// transfer across the JS/WASM boundary.
+#[cfg(feature = "binary")]
+#[wasm_bindgen]
+pub fn analyze_all_grades_bin(grades: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let grades: Vec<Grade> = rmp_serde::from_slice(grades)
+        .map_err(|e| JsValue::from_str(&format!("Failed to decode grades: {}", e)))?;
+
+    let result = grades::analyze_all(&grades);
+
+    rmp_serde::to_vec(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to encode result: {}", e)))
+}
+
+/// Compare a current `AnalyticsResult` against a previously saved baseline
+/// (e.g. taken at the start of a term) and report what moved since then.
+#[wasm_bindgen]
+pub fn compare_to_baseline(current_json: &str, baseline_json: &str) -> Result<String, JsValue> {
+    let current: AnalyticsResult = serde_json::from_str(current_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse current result: {}", e)))?;
+    let baseline: AnalyticsResult = serde_json::from_str(baseline_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse baseline result: {}", e)))?;
+
+    let delta = comparison::compare_to_baseline(&current, &baseline, comparison::STABLE_THRESHOLD);
+
+    serde_json::to_string(&delta)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize delta: {}", e)))
+}
+
 // ============================================================================
 // WASM Exports - Statistics Functions
 // ============================================================================
@@ -277,6 +679,48 @@ pub fn calculate_statistics(data_json: &str) -> Result<String, JsValue> {
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize statistics: {}", e)))
 }
 
+/// Calculate comprehensive statistics for a data set, reading and writing
+/// MessagePack instead of JSON.
+#[cfg(feature = "binary")]
+#[wasm_bindgen]
+pub fn calculate_statistics_bin(data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let data: Vec<f64> = rmp_serde::from_slice(data)
+        .map_err(|e| JsValue::from_str(&format!("Failed to decode data: {}", e)))?;
+
+    let stats = statistics::calculate_statistics(&data);
+
+    rmp_serde::to_vec(&stats)
+        .map_err(|e| JsValue::from_str(&format!("Failed to encode statistics: {}", e)))
+}
+
+/// Calculate statistics along with a bootstrap confidence interval on the mean
+#[wasm_bindgen]
+pub fn calculate_statistics_with_ci(
+    data_json: &str,
+    confidence: f64,
+    seed: u64,
+) -> Result<String, JsValue> {
+    let data: Vec<f64> = serde_json::from_str(data_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse data: {}", e)))?;
+
+    let stats = statistics::calculate_statistics(&data);
+    let mean_ci = statistics::bootstrap_statistic(
+        &data,
+        statistics::calculate_mean,
+        statistics::DEFAULT_BOOTSTRAP_RESAMPLES,
+        confidence,
+        seed,
+    );
+
+    let result = StatisticsWithCi {
+        statistics: stats,
+        mean_ci,
+    };
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
 /// Calculate a specific percentile
 #[wasm_bindgen]
 pub fn calculate_percentile(data_json: &str, percentile: f64) -> Result<f64, JsValue> {
@@ -286,14 +730,32 @@ pub fn calculate_percentile(data_json: &str, percentile: f64) -> Result<f64, JsV
     Ok(statistics::calculate_percentile(&data, percentile))
 }
 
+/// Approximate a percentile from a log-scaled histogram over `data`,
+/// optionally rejecting IQR outliers before bucketing so a handful of
+/// extreme values don't stretch the bucket range
+#[wasm_bindgen]
+pub fn histogram_quantile(
+    data_json: &str,
+    relative_precision: f64,
+    exclude_outliers: bool,
+    percentile: f64,
+) -> Result<f64, JsValue> {
+    let data: Vec<f64> = serde_json::from_str(data_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse data: {}", e)))?;
+
+    let hist = statistics::LogHistogram::from_values(&data, relative_precision, exclude_outliers);
+
+    Ok(hist.quantile(percentile))
+}
+
 /// Calculate trend from time series data
 #[wasm_bindgen]
 pub fn calculate_trend(data_json: &str) -> Result<String, JsValue> {
     let data: Vec<(i64, f64)> = serde_json::from_str(data_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse data: {}", e)))?;
     
-    let trend = statistics::calculate_trend(&data);
-    
+    let trend = statistics::calculate_trend_with_ci(&data);
+
     serde_json::to_string(&trend)
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize trend: {}", e)))
 }
@@ -305,10 +767,102 @@ pub fn calculate_correlation(data1_json: &str, data2_json: &str) -> Result<f64,
         .map_err(|e| JsValue::from_str(&format!("Failed to parse data1: {}", e)))?;
     let data2: Vec<f64> = serde_json::from_str(data2_json)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse data2: {}", e)))?;
-    
+
     Ok(statistics::calculate_correlation(&data1, &data2))
 }
 
+/// Opaque handle around a `StatsAccumulator` so the extension can stream
+/// grades in as they arrive instead of recomputing statistics from a full
+/// `Vec<f64>` on every update.
+#[wasm_bindgen]
+pub struct StatsAccumulator(statistics::StatsAccumulator);
+
+#[wasm_bindgen]
+impl StatsAccumulator {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(statistics::StatsAccumulator::new())
+    }
+
+    /// Fold a single new value into the running moments.
+    pub fn push(&mut self, value: f64) {
+        self.0.push(value);
+    }
+
+    /// Convenience over `push` for callers streaming `Grade`s directly,
+    /// folding in only the grade's value (its weight is ignored, same as
+    /// `push`'s caller would have to do manually).
+    pub fn push_grade(&mut self, grade_json: &str) -> Result<(), JsValue> {
+        let grade: Grade = serde_json::from_str(grade_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse grade: {}", e)))?;
+        self.0.push(grade.value);
+        Ok(())
+    }
+
+    /// Combine with another accumulator built from a disjoint batch.
+    pub fn merge(&mut self, other: &StatsAccumulator) {
+        self.0.merge(&other.0);
+    }
+
+    /// Produce a point-in-time `Statistics` snapshot from the running moments.
+    pub fn snapshot(&self) -> Result<String, JsValue> {
+        serde_json::to_string(&self.0.snapshot())
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize snapshot: {}", e)))
+    }
+}
+
+impl Default for StatsAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Opaque handle around a `grades::WeightedIncrementalStats` so the
+/// extension can stream grades in as they arrive and get a running
+/// weighted mean/variance that matches `calculate_weighted_average`,
+/// instead of the unweighted moments `StatsAccumulator` tracks.
+#[wasm_bindgen]
+pub struct WeightedStatsAccumulator(grades::WeightedIncrementalStats);
+
+#[wasm_bindgen]
+impl WeightedStatsAccumulator {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(grades::WeightedIncrementalStats::new())
+    }
+
+    /// Fold one more grade's `(value, weight)` pair into the running moments.
+    pub fn push_grade(&mut self, grade_json: &str) -> Result<(), JsValue> {
+        let grade: Grade = serde_json::from_str(grade_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse grade: {}", e)))?;
+        self.0.add(&grade);
+        Ok(())
+    }
+
+    /// Combine with another accumulator built from a disjoint batch of grades.
+    pub fn merge(&mut self, other: &WeightedStatsAccumulator) {
+        self.0.merge(&other.0);
+    }
+
+    pub fn sum_weight(&self) -> f64 {
+        self.0.sum_weight()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.0.mean()
+    }
+
+    pub fn variance(&self) -> f64 {
+        self.0.variance()
+    }
+}
+
+impl Default for WeightedStatsAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ============================================================================
 // WASM Exports - Prediction Functions
 // ============================================================================
@@ -341,6 +895,54 @@ pub fn predict_next_grade(grades_json: &str) -> Result<String, JsValue> {
         .map_err(|e| JsValue::from_str(&format!("Failed to serialize prediction: {}", e)))
 }
 
+/// Backtest the ensemble's base methods and report their derived weights
+#[wasm_bindgen]
+pub fn backtest_methods(grades_json: &str) -> Result<String, JsValue> {
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let report = predictions::backtest_methods(&grades);
+
+    serde_json::to_string(&report)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize report: {}", e)))
+}
+
+/// Build a per-subject review schedule using the FSRS-style power-law
+/// forgetting curve, ordered by soonest next review first
+#[wasm_bindgen]
+pub fn fsrs_review_schedule(grades_json: &str, now: i64, target_retention: f64) -> Result<String, JsValue> {
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let schedule = predictions::fsrs_review_schedule(&grades, now, target_retention);
+
+    serde_json::to_string(&schedule)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize schedule: {}", e)))
+}
+
+/// Fit the EMA smoothing factor and regression recency-weighting exponent
+/// to a student's grade history via gradient descent
+#[wasm_bindgen]
+pub fn calibrate_parameters(
+    grades_json: &str,
+    learning_rate: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<String, JsValue> {
+    let grades: Vec<Grade> = serde_json::from_str(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    let config = GradientDescentConfig {
+        learning_rate,
+        tolerance,
+        max_iterations,
+    };
+    let result = predictions::calibrate_parameters(&grades, &config);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
 /// Calculate what-if scenario
 #[wasm_bindgen]
 pub fn calculate_whatif(grades_json: &str, hypothetical_json: &str) -> Result<String, JsValue> {
@@ -410,11 +1012,27 @@ pub fn format_grade(value: f64, decimals: u32) -> String {
 /// Parse a Dutch-formatted grade string
 #[wasm_bindgen]
 pub fn parse_grade(grade_str: &str) -> Result<f64, JsValue> {
-    let normalized = grade_str.replace(',', ".");
-    normalized.parse::<f64>()
+    parse_dutch_number(grade_str)
         .map_err(|e| JsValue::from_str(&format!("Failed to parse grade: {}", e)))
 }
 
+/// Parse a batch of scraped grade JSON leniently: individual malformed
+/// records are rejected with a reason instead of failing the whole batch.
+#[wasm_bindgen]
+pub fn parse_grades_lenient(grades_json: &str) -> Result<String, JsValue> {
+    let result = grades::parse_grades_lenient(grades_json)
+        .map_err(|e| JsValue::from_str(&format!("Failed to parse grades: {}", e)))?;
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {}", e)))
+}
+
+/// Classify a grade value into its qualitative Dutch rating band
+#[wasm_bindgen]
+pub fn classify_grade(value: f64) -> String {
+    GradeBand::from_value(value).as_str().to_string()
+}
+
 /// Get version information
 #[wasm_bindgen]
 pub fn get_version() -> String {
@@ -456,4 +1074,47 @@ mod tests {
         assert_eq!(format_grade(8.5, 1), "8,5");
         assert_eq!(format_grade(7.25, 2), "7,25");
     }
+
+    #[test]
+    fn test_classify_grade() {
+        assert_eq!(classify_grade(4.0), "Onvoldoende");
+        assert_eq!(classify_grade(5.5), "Zwak");
+        assert_eq!(classify_grade(9.5), "Uitstekend");
+    }
+
+    #[test]
+    fn test_grade_band_round_trips_through_display_and_from_str() {
+        let band = GradeBand::from_value(8.2);
+        let parsed: GradeBand = band.to_string().parse().unwrap();
+        assert_eq!(band, parsed);
+    }
+
+    #[test]
+    fn test_stats_accumulator_push_grade_matches_push_value() {
+        let grade = Grade::new(8.5, 1.0, "Math".to_string(), "Test 1".to_string(), 1234567890);
+        let grade_json = serde_json::to_string(&grade).unwrap();
+
+        let mut by_grade = StatsAccumulator::new();
+        by_grade.push_grade(&grade_json).unwrap();
+
+        let mut by_value = StatsAccumulator::new();
+        by_value.push(grade.value);
+
+        assert_eq!(by_grade.snapshot().unwrap(), by_value.snapshot().unwrap());
+    }
+
+    #[test]
+    fn test_weighted_stats_accumulator_matches_calculate_weighted_average() {
+        let grades = vec![
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(6.0, 2.0, "Math".to_string(), "Test 2".to_string(), 1),
+        ];
+
+        let mut acc = WeightedStatsAccumulator::new();
+        for grade in &grades {
+            acc.push_grade(&serde_json::to_string(grade).unwrap()).unwrap();
+        }
+
+        assert!((acc.mean() - grades::calculate_weighted_average(&grades)).abs() < 1e-9);
+    }
 }
@@ -4,7 +4,10 @@
 //! using various statistical methods including linear regression,
 //! exponential smoothing, and machine learning-inspired approaches.
 
-use crate::{Grade, PredictionResult, WhatIfResult, GradeNeeded, ImpactEntry};
+use crate::{
+    BacktestReport, CalibratedParameters, Grade, GradientDescentConfig, PassProbabilityResult,
+    PredictionResult, ReviewSchedule, WhatIfResult, GradeNeeded, ImpactEntry,
+};
 use crate::statistics;
 
 /// Predict the grade needed to achieve a target average
@@ -53,24 +56,66 @@ pub fn predict_next_grade(grades: &[Grade]) -> PredictionResult {
     // Sort grades by timestamp
     let mut sorted_grades = grades.to_vec();
     sorted_grades.sort_by_key(|g| g.timestamp);
-    
+
+    // Once there's enough history for its engineered features to be
+    // meaningful, the gradient-boosted regressor captures nonlinear and
+    // seasonal structure the ensemble below can't, so prefer it outright
+    // rather than folding it in as one more weighted vote.
+    if sorted_grades.len() >= GBDT_MIN_POINTS {
+        return predict_from_gbdt(&sorted_grades);
+    }
+
+    // Fit the EMA alpha and regression exponent to this student's own
+    // history when there's enough of it to backtest; otherwise keep the
+    // defaults rather than pay for a gradient descent that has nothing to
+    // learn from.
+    let calibrated = if sorted_grades.len() >= 4 {
+        Some(calibrate_parameters(&sorted_grades, &GradientDescentConfig::default()))
+    } else {
+        None
+    };
+    let alpha = calibrated.as_ref().map(|c| c.alpha).unwrap_or(DEFAULT_EMA_ALPHA);
+    let regression_exponent = calibrated
+        .as_ref()
+        .map(|c| c.regression_exponent)
+        .unwrap_or(DEFAULT_REGRESSION_EXPONENT);
+
     // Try multiple prediction methods and combine results
     let trend_prediction = predict_from_trend(&sorted_grades);
-    let ema_prediction = predict_from_ema(&sorted_grades);
-    let regression_prediction = predict_from_regression(&sorted_grades);
-    
-    // Weight the predictions based on data characteristics
-    let trend_weight = if sorted_grades.len() >= 5 { 0.4 } else { 0.2 };
-    let ema_weight = 0.3;
-    let regression_weight = 1.0 - trend_weight - ema_weight;
-    
-    let combined_prediction = 
+    let ema_prediction = predict_from_ema_with_alpha(&sorted_grades, alpha);
+    let regression_prediction =
+        predict_from_regression_with_params(&sorted_grades, regression_exponent, DEFAULT_PREDICTION_CONFIDENCE);
+    let isotonic_prediction = predict_from_isotonic(&sorted_grades);
+
+    // Weight the predictions by how well each method actually backtests on
+    // this student's history, falling back to the old fixed split when
+    // there isn't enough history to backtest.
+    let report = backtest_methods(&sorted_grades);
+    let (trend_weight, ema_weight, regression_weight, isotonic_weight) = if report.folds > 0 {
+        (
+            report.trend_weight,
+            report.ema_weight,
+            report.regression_weight,
+            report.isotonic_weight,
+        )
+    } else {
+        let trend_weight = if sorted_grades.len() >= 5 { 0.4 } else { 0.2 };
+        let ema_weight = 0.3;
+        (trend_weight, ema_weight, 1.0 - trend_weight - ema_weight, 0.0)
+    };
+
+    let combined_prediction =
         trend_prediction.predicted_value * trend_weight +
         ema_prediction.predicted_value * ema_weight +
-        regression_prediction.predicted_value * regression_weight;
-    
+        regression_prediction.predicted_value * regression_weight +
+        isotonic_prediction.predicted_value * isotonic_weight;
+
     // Calculate combined confidence
-    let avg_confidence = (trend_prediction.confidence + ema_prediction.confidence + regression_prediction.confidence) / 3.0;
+    let avg_confidence = (trend_prediction.confidence
+        + ema_prediction.confidence
+        + regression_prediction.confidence
+        + isotonic_prediction.confidence)
+        / 4.0;
     
     // Calculate bounds based on historical variance
     let values: Vec<f64> = sorted_grades.iter().map(|g| g.value).collect();
@@ -88,42 +133,298 @@ pub fn predict_next_grade(grades: &[Grade]) -> PredictionResult {
     }
 }
 
+/// Walk forward over `grades` sorted by timestamp, scoring each base
+/// predictor's one-step-ahead accuracy against the actual next grade, and
+/// derive ensemble weights from the inverse of each method's mean absolute
+/// error. `predict_next_grade` uses these instead of fixed constants once
+/// there's enough history to backtest.
+pub fn backtest_methods(grades: &[Grade]) -> BacktestReport {
+    let mut sorted_grades = grades.to_vec();
+    sorted_grades.sort_by_key(|g| g.timestamp);
+
+    let mut trend_errors = Vec::new();
+    let mut ema_errors = Vec::new();
+    let mut regression_errors = Vec::new();
+    let mut isotonic_errors = Vec::new();
+
+    for k in 2..sorted_grades.len() {
+        let prefix = &sorted_grades[..k];
+        let actual = sorted_grades[k].value;
+
+        trend_errors.push((predict_from_trend(prefix).predicted_value - actual).abs());
+        ema_errors.push((predict_from_ema(prefix).predicted_value - actual).abs());
+        regression_errors.push((predict_from_regression(prefix).predicted_value - actual).abs());
+        isotonic_errors.push((predict_from_isotonic(prefix).predicted_value - actual).abs());
+    }
+
+    let folds = trend_errors.len();
+    let (trend_mae, trend_rmse) = mae_rmse(&trend_errors);
+    let (ema_mae, ema_rmse) = mae_rmse(&ema_errors);
+    let (regression_mae, regression_rmse) = mae_rmse(&regression_errors);
+    let (isotonic_mae, isotonic_rmse) = mae_rmse(&isotonic_errors);
+
+    let (trend_weight, ema_weight, regression_weight, isotonic_weight) = if folds == 0 {
+        (0.0, 0.0, 0.0, 0.0)
+    } else {
+        inverse_error_weights(trend_mae, ema_mae, regression_mae, isotonic_mae)
+    };
+
+    BacktestReport {
+        folds,
+        trend_mae,
+        trend_rmse,
+        trend_weight,
+        ema_mae,
+        ema_rmse,
+        ema_weight,
+        regression_mae,
+        regression_rmse,
+        regression_weight,
+        isotonic_mae,
+        isotonic_rmse,
+        isotonic_weight,
+    }
+}
+
+/// Walk-forward mean squared error for the EMA predictor at a given
+/// `alpha`, the loss `calibrate_parameters` minimizes for that parameter.
+fn ema_backtest_loss(grades: &[Grade], alpha: f64) -> f64 {
+    let mut sse = 0.0;
+    let mut folds = 0usize;
+    for k in 2..grades.len() {
+        let predicted = predict_from_ema_with_alpha(&grades[..k], alpha).predicted_value;
+        sse += (predicted - grades[k].value).powi(2);
+        folds += 1;
+    }
+    if folds == 0 {
+        0.0
+    } else {
+        sse / folds as f64
+    }
+}
+
+/// Walk-forward mean squared error for the weighted-regression predictor at
+/// a given recency-weighting `exponent`, the loss `calibrate_parameters`
+/// minimizes for that parameter.
+fn regression_backtest_loss(grades: &[Grade], exponent: f64) -> f64 {
+    let mut sse = 0.0;
+    let mut folds = 0usize;
+    for k in 2..grades.len() {
+        let predicted =
+            predict_from_regression_with_params(&grades[..k], exponent, DEFAULT_PREDICTION_CONFIDENCE)
+                .predicted_value;
+        sse += (predicted - grades[k].value).powi(2);
+        folds += 1;
+    }
+    if folds == 0 {
+        0.0
+    } else {
+        sse / folds as f64
+    }
+}
+
+/// Fit the EMA smoothing factor and the regression recency-weighting
+/// exponent to `grades` by batch gradient descent, minimizing walk-forward
+/// squared prediction error. The two parameters don't interact in the loss,
+/// so each is descended independently; gradients are estimated numerically
+/// by central finite differences since the backtest loss has no closed
+/// form. Returns the fitted parameters plus the loss curve so callers can
+/// see convergence.
+pub fn calibrate_parameters(grades: &[Grade], config: &GradientDescentConfig) -> CalibratedParameters {
+    let mut sorted_grades = grades.to_vec();
+    sorted_grades.sort_by_key(|g| g.timestamp);
+
+    let mut alpha = DEFAULT_EMA_ALPHA;
+    let mut exponent = DEFAULT_REGRESSION_EXPONENT;
+
+    let loss = |a: f64, e: f64| ema_backtest_loss(&sorted_grades, a) + regression_backtest_loss(&sorted_grades, e);
+
+    let mut loss_curve = vec![loss(alpha, exponent)];
+    let mut iterations = 0;
+
+    if sorted_grades.len() < 4 {
+        return CalibratedParameters {
+            alpha,
+            regression_exponent: exponent,
+            loss_curve,
+            iterations,
+        };
+    }
+
+    const H: f64 = 1e-3;
+    let mut prev_loss = loss_curve[0];
+
+    for _ in 0..config.max_iterations {
+        let grad_alpha =
+            (loss((alpha + H).min(0.999), exponent) - loss((alpha - H).max(0.001), exponent)) / (2.0 * H);
+        let grad_exponent = (loss(alpha, exponent + H) - loss(alpha, exponent - H)) / (2.0 * H);
+
+        alpha = (alpha - config.learning_rate * grad_alpha).clamp(0.001, 0.999);
+        exponent = (exponent - config.learning_rate * grad_exponent).max(0.1);
+
+        let current_loss = loss(alpha, exponent);
+        loss_curve.push(current_loss);
+        iterations += 1;
+
+        if (prev_loss - current_loss).abs() < config.tolerance {
+            break;
+        }
+        prev_loss = current_loss;
+    }
+
+    CalibratedParameters {
+        alpha,
+        regression_exponent: exponent,
+        loss_curve,
+        iterations,
+    }
+}
+
+fn mae_rmse(errors: &[f64]) -> (f64, f64) {
+    if errors.is_empty() {
+        return (0.0, 0.0);
+    }
+    let n = errors.len() as f64;
+    let mae = errors.iter().sum::<f64>() / n;
+    let rmse = (errors.iter().map(|e| e.powi(2)).sum::<f64>() / n).sqrt();
+    (mae, rmse)
+}
+
+/// Convert per-method mean absolute errors into normalized inverse-error
+/// weights (`w_m = (1/e_m) / Σ(1/e_j)`), flooring each error so a method
+/// that happens to backtest perfectly doesn't divide by zero.
+fn inverse_error_weights(
+    trend_mae: f64,
+    ema_mae: f64,
+    regression_mae: f64,
+    isotonic_mae: f64,
+) -> (f64, f64, f64, f64) {
+    const EPSILON: f64 = 1e-6;
+    let inv_trend = 1.0 / trend_mae.max(EPSILON);
+    let inv_ema = 1.0 / ema_mae.max(EPSILON);
+    let inv_regression = 1.0 / regression_mae.max(EPSILON);
+    let inv_isotonic = 1.0 / isotonic_mae.max(EPSILON);
+    let total = inv_trend + inv_ema + inv_regression + inv_isotonic;
+
+    (
+        inv_trend / total,
+        inv_ema / total,
+        inv_regression / total,
+        inv_isotonic / total,
+    )
+}
+
+/// Default confidence level used for regression prediction intervals when
+/// the caller doesn't pick one explicitly.
+const DEFAULT_PREDICTION_CONFIDENCE: f64 = 0.95;
+
+/// Default EMA smoothing factor, used until `calibrate_parameters` has
+/// fitted one to a particular student's history.
+const DEFAULT_EMA_ALPHA: f64 = 0.3;
+
+/// Default recency-weighting exponent for `predict_from_regression`, used
+/// until `calibrate_parameters` has fitted one to a particular student's
+/// history.
+const DEFAULT_REGRESSION_EXPONENT: f64 = 2.0;
+
+/// Estimate the timestamp of the "next" grade (as an offset from the first
+/// grade's timestamp) by extrapolating the average interval between the
+/// grades seen so far.
+fn estimate_next_time_offset(grades: &[Grade]) -> f64 {
+    let first_time = grades.first().map(|g| g.timestamp).unwrap_or(0);
+    let last_time = grades.last().map(|g| g.timestamp).unwrap_or(0);
+    let avg_interval = if grades.len() > 1 {
+        (last_time - first_time) / (grades.len() - 1) as i64
+    } else {
+        86400000 // Default 1 day in milliseconds
+    };
+
+    (last_time - first_time) as f64 + avg_interval as f64
+}
+
+/// Half-width of a `confidence`-level prediction interval for a line fit
+/// through `data` (already time-offset, not raw timestamps), evaluated at
+/// the next point `x0`: `t * s * sqrt(1 + 1/n + (x0 - x̄)² / Sxx)`, where `s`
+/// is the residual standard error and `t` is a Student's-t quantile with
+/// `n - 2` degrees of freedom. Falls back to the old fixed `±1.5` band when
+/// there isn't enough data to estimate residual spread.
+fn regression_interval_half_width(
+    data: &[(f64, f64)],
+    slope: f64,
+    intercept: f64,
+    x0: f64,
+    confidence: f64,
+) -> f64 {
+    let n = data.len();
+    if n < 3 {
+        return 1.5;
+    }
+
+    let mean_x = data.iter().map(|(x, _)| *x).sum::<f64>() / n as f64;
+    let sxx: f64 = data.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    if sxx <= 0.0 {
+        return 1.5;
+    }
+
+    let sse: f64 = data
+        .iter()
+        .map(|(x, y)| (y - (slope * x + intercept)).powi(2))
+        .sum();
+    let s = (sse / (n as f64 - 2.0)).sqrt();
+
+    let alpha = (1.0 - confidence).clamp(1e-6, 1.0);
+    let t = statistics::t_quantile(1.0 - alpha / 2.0, n as f64 - 2.0);
+
+    t * s * (1.0 + 1.0 / n as f64 + (x0 - mean_x).powi(2) / sxx).sqrt()
+}
+
 /// Predict using linear trend
 fn predict_from_trend(grades: &[Grade]) -> PredictionResult {
+    predict_from_trend_with_confidence(grades, DEFAULT_PREDICTION_CONFIDENCE)
+}
+
+/// Like `predict_from_trend`, with a caller-chosen confidence level for the
+/// prediction interval.
+fn predict_from_trend_with_confidence(grades: &[Grade], confidence: f64) -> PredictionResult {
     let time_series: Vec<(i64, f64)> = grades
         .iter()
         .map(|g| (g.timestamp, g.value))
         .collect();
-    
+
     let trend = statistics::calculate_trend(&time_series);
-    
-    // Predict next value based on trend
-    let last_time = grades.last().map(|g| g.timestamp).unwrap_or(0);
-    let avg_interval = if grades.len() > 1 {
-        let first_time = grades.first().map(|g| g.timestamp).unwrap_or(0);
-        (last_time - first_time) / (grades.len() - 1) as i64
-    } else {
-        86400000 // Default 1 day in milliseconds
-    };
-    
-    let next_time = (last_time - grades.first().map(|g| g.timestamp).unwrap_or(0)) as f64 + avg_interval as f64;
+
+    let first_time = grades.first().map(|g| g.timestamp).unwrap_or(0);
+    let next_time = estimate_next_time_offset(grades);
     let predicted_value = trend.slope * next_time + trend.intercept;
-    
+
+    let normalized: Vec<(f64, f64)> = time_series
+        .iter()
+        .map(|(t, v)| ((t - first_time) as f64, *v))
+        .collect();
+    let half_width =
+        regression_interval_half_width(&normalized, trend.slope, trend.intercept, next_time, confidence);
+
     PredictionResult {
         predicted_value: predicted_value.clamp(1.0, 10.0),
         confidence: trend.r_squared,
-        lower_bound: (predicted_value - 1.5).max(1.0),
-        upper_bound: (predicted_value + 1.5).min(10.0),
+        lower_bound: (predicted_value - half_width).max(1.0),
+        upper_bound: (predicted_value + half_width).min(10.0),
         method: "trend".to_string(),
     }
 }
 
 /// Predict using exponential moving average
 fn predict_from_ema(grades: &[Grade]) -> PredictionResult {
+    predict_from_ema_with_alpha(grades, DEFAULT_EMA_ALPHA)
+}
+
+/// Like `predict_from_ema`, with a caller-chosen smoothing factor. Used
+/// directly by `calibrate_parameters`'s backtest loss, and by
+/// `predict_next_grade` once it has a calibrated `alpha` for this history.
+fn predict_from_ema_with_alpha(grades: &[Grade], alpha: f64) -> PredictionResult {
     let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
-    
-    // Use alpha = 0.3 for EMA (more weight on recent values)
-    let ema = statistics::calculate_ema(&values, 0.3);
+
+    let ema = statistics::calculate_ema(&values, alpha);
     let predicted_value = ema.last().copied().unwrap_or(0.0);
     
     // Confidence based on how consistent the EMA has been
@@ -146,6 +447,25 @@ fn predict_from_ema(grades: &[Grade]) -> PredictionResult {
 
 /// Predict using polynomial regression
 fn predict_from_regression(grades: &[Grade]) -> PredictionResult {
+    predict_from_regression_with_params(grades, DEFAULT_REGRESSION_EXPONENT, DEFAULT_PREDICTION_CONFIDENCE)
+}
+
+/// Like `predict_from_regression`, with a caller-chosen confidence level for
+/// the prediction interval.
+fn predict_from_regression_with_confidence(grades: &[Grade], confidence: f64) -> PredictionResult {
+    predict_from_regression_with_params(grades, DEFAULT_REGRESSION_EXPONENT, confidence)
+}
+
+/// Like `predict_from_regression`, with a caller-chosen recency-weighting
+/// `exponent` (the original used a fixed quadratic, `exponent = 2.0`) on top
+/// of a chosen confidence level. Used directly by `calibrate_parameters`'s
+/// backtest loss, and by `predict_next_grade` once it has a calibrated
+/// exponent for this history.
+fn predict_from_regression_with_params(
+    grades: &[Grade],
+    exponent: f64,
+    confidence: f64,
+) -> PredictionResult {
     if grades.len() < 3 {
         let avg = grades.iter().map(|g| g.value).sum::<f64>() / grades.len() as f64;
         return PredictionResult {
@@ -156,31 +476,392 @@ fn predict_from_regression(grades: &[Grade]) -> PredictionResult {
             method: "simple_average".to_string(),
         };
     }
-    
+
     // Use weighted recent average with exponential decay
-    let n = grades.len();
     let mut weighted_sum = 0.0;
     let mut weight_total = 0.0;
-    
+
     for (i, grade) in grades.iter().enumerate() {
-        let weight = (i as f64 + 1.0).powi(2); // Quadratic weighting
+        let weight = (i as f64 + 1.0).powf(exponent);
         weighted_sum += grade.value * weight;
         weight_total += weight;
     }
-    
+
     let predicted_value = weighted_sum / weight_total;
-    
+
     // Calculate confidence based on consistency
     let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
     let cv = statistics::calculate_cv(&values);
-    let confidence = (100.0 - cv.min(100.0)) / 100.0;
-    
+    let point_confidence = (100.0 - cv.min(100.0)) / 100.0;
+
+    // Fit an auxiliary line over the (time, value) series to derive a
+    // statistically correct interval width around the weighted-average
+    // point estimate above.
+    let first_time = grades.first().map(|g| g.timestamp).unwrap_or(0);
+    let time_series: Vec<(i64, f64)> = grades.iter().map(|g| (g.timestamp, g.value)).collect();
+    let trend = statistics::calculate_trend(&time_series);
+    let normalized: Vec<(f64, f64)> = time_series
+        .iter()
+        .map(|(t, v)| ((t - first_time) as f64, *v))
+        .collect();
+    let x0 = estimate_next_time_offset(grades);
+    let half_width =
+        regression_interval_half_width(&normalized, trend.slope, trend.intercept, x0, confidence);
+
+    PredictionResult {
+        predicted_value: predicted_value.clamp(1.0, 10.0),
+        confidence: point_confidence.clamp(0.1, 0.9),
+        lower_bound: (predicted_value - half_width).max(1.0),
+        upper_bound: (predicted_value + half_width).min(10.0),
+        method: "weighted_regression".to_string(),
+    }
+}
+
+/// A pooled run of equal fitted values produced by the Pool Adjacent
+/// Violators Algorithm.
+struct IsotonicBlock {
+    level: f64,
+    weight: f64,
+    count: usize,
+}
+
+/// Fit the best weighted-least-squares monotone step function through
+/// `values` (pairs of `(value, weight)`) via the Pool Adjacent Violators
+/// Algorithm: push each point as a singleton block, then merge backwards by
+/// weight-averaging whenever a block's level violates monotonicity with its
+/// predecessor. Non-decreasing when `non_decreasing` is true, non-increasing
+/// otherwise. Runs in O(n).
+fn pool_adjacent_violators(values: &[(f64, f64)], non_decreasing: bool) -> Vec<IsotonicBlock> {
+    let mut blocks: Vec<IsotonicBlock> = Vec::new();
+
+    for &(value, weight) in values {
+        blocks.push(IsotonicBlock {
+            level: value,
+            weight,
+            count: 1,
+        });
+
+        while blocks.len() >= 2 {
+            let last = &blocks[blocks.len() - 1];
+            let prev = &blocks[blocks.len() - 2];
+            let violates = if non_decreasing {
+                last.level < prev.level
+            } else {
+                last.level > prev.level
+            };
+
+            if !violates {
+                break;
+            }
+
+            let last = blocks.pop().unwrap();
+            let prev = blocks.pop().unwrap();
+            let merged_weight = prev.weight + last.weight;
+            let merged_level = (prev.level * prev.weight + last.level * last.weight) / merged_weight;
+
+            blocks.push(IsotonicBlock {
+                level: merged_level,
+                weight: merged_weight,
+                count: prev.count + last.count,
+            });
+        }
+    }
+
+    blocks
+}
+
+/// Expand pooled blocks back into one fitted value per original point, in
+/// order, for computing residuals against the raw series.
+fn expand_isotonic_blocks(blocks: &[IsotonicBlock]) -> Vec<f64> {
+    blocks
+        .iter()
+        .flat_map(|block| std::iter::repeat(block.level).take(block.count))
+        .collect()
+}
+
+/// Predict using isotonic (monotone) regression, for students whose grades
+/// are steadily improving or declining so the ensemble isn't fooled by a
+/// single noisy dip. Detects the trend direction first, then fits a
+/// monotone step function in that direction via the Pool Adjacent Violators
+/// Algorithm and extrapolates from its last two distinct levels.
+fn predict_from_isotonic(grades: &[Grade]) -> PredictionResult {
+    if grades.len() < 3 {
+        let avg = grades.iter().map(|g| g.value).sum::<f64>() / grades.len() as f64;
+        return PredictionResult {
+            predicted_value: avg,
+            confidence: 0.2,
+            lower_bound: (avg - 1.5).max(1.0),
+            upper_bound: (avg + 1.5).min(10.0),
+            method: "isotonic".to_string(),
+        };
+    }
+
+    let time_series: Vec<(i64, f64)> = grades.iter().map(|g| (g.timestamp, g.value)).collect();
+    let trend = statistics::calculate_trend(&time_series);
+    let non_decreasing = trend.slope >= 0.0;
+
+    let weighted: Vec<(f64, f64)> = grades.iter().map(|g| (g.value, g.weight.max(1e-6))).collect();
+    let blocks = pool_adjacent_violators(&weighted, non_decreasing);
+
+    let last_level = blocks.last().map(|b| b.level).unwrap_or(0.0);
+    let predicted_value = if blocks.len() >= 2 {
+        let second_last_level = blocks[blocks.len() - 2].level;
+        last_level + (last_level - second_last_level)
+    } else {
+        last_level
+    };
+
+    // Confidence from the fraction of variance the monotone fit explains.
+    let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
+    let mean = statistics::calculate_mean(&values);
+    let total_variance: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+    let fitted = expand_isotonic_blocks(&blocks);
+    let residual_variance: f64 = values.iter().zip(&fitted).map(|(v, f)| (v - f).powi(2)).sum();
+    let r_squared = if total_variance > 0.0 {
+        (1.0 - residual_variance / total_variance).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
     PredictionResult {
         predicted_value: predicted_value.clamp(1.0, 10.0),
-        confidence: confidence.clamp(0.1, 0.9),
+        confidence: r_squared.clamp(0.1, 0.95),
         lower_bound: (predicted_value - 1.5).max(1.0),
         upper_bound: (predicted_value + 1.5).min(10.0),
-        method: "weighted_regression".to_string(),
+        method: "isotonic".to_string(),
+    }
+}
+
+/// Minimum number of grades before `predict_from_gbdt` trains its own
+/// model; below this there isn't enough history to fit engineered
+/// features reliably, so it defers to `predict_next_grade`'s ensemble.
+const GBDT_MIN_POINTS: usize = 12;
+const GBDT_NUM_TREES: usize = 20;
+const GBDT_LEARNING_RATE: f64 = 0.1;
+const GBDT_MAX_DEPTH: usize = 2;
+const GBDT_LAG_COUNT: usize = 5;
+const GBDT_FFT_BINS: usize = 3;
+
+/// One training example for the gradient-boosted predictor: an engineered
+/// feature vector built from everything up to (but not including) a point,
+/// paired with that point's value as the regression target.
+struct GbdtSample {
+    features: Vec<f64>,
+    target: f64,
+}
+
+/// A node of a shallow regression tree: either a leaf prediction or an
+/// axis-aligned split on one feature.
+enum GbdtNode {
+    Leaf(f64),
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: Box<GbdtNode>,
+        right: Box<GbdtNode>,
+    },
+}
+
+/// Magnitudes of the first `num_bins` frequency components of `values`,
+/// via a naive discrete Fourier transform — history lengths here are small
+/// enough that an O(n*bins) DFT is cheaper than pulling in an FFT crate.
+fn dft_magnitudes(values: &[f64], num_bins: usize) -> Vec<f64> {
+    let n = values.len();
+    (0..num_bins.min(n))
+        .map(|k| {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (t, &value) in values.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (k as f64) * (t as f64) / (n as f64);
+                re += value * angle.cos();
+                im += value * angle.sin();
+            }
+            (re * re + im * im).sqrt()
+        })
+        .chain(std::iter::repeat(0.0))
+        .take(num_bins)
+        .collect()
+}
+
+/// Build the feature vector for the point that would follow `values`: the
+/// last `GBDT_LAG_COUNT` values and their differences, a rolling mean and
+/// variance over that window, the assessment's weight, and a handful of
+/// low-frequency FFT magnitudes capturing periodic (e.g. term-cycle)
+/// structure.
+fn build_gbdt_features(values: &[f64], weight: f64) -> Vec<f64> {
+    let n = values.len();
+    let mut features = Vec::with_capacity(GBDT_LAG_COUNT * 2 + 3 + GBDT_FFT_BINS);
+
+    for lag in 1..=GBDT_LAG_COUNT {
+        features.push(if lag <= n { values[n - lag] } else { values[0] });
+    }
+    for lag in 1..=GBDT_LAG_COUNT {
+        let cur = if lag <= n { values[n - lag] } else { values[0] };
+        let prev = if lag + 1 <= n { values[n - lag - 1] } else { cur };
+        features.push(cur - prev);
+    }
+
+    let window_len = GBDT_LAG_COUNT.min(n).max(1);
+    let window = &values[n - window_len..];
+    let mean = window.iter().sum::<f64>() / window_len as f64;
+    let variance = window.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window_len as f64;
+    features.push(mean);
+    features.push(variance);
+    features.push(weight);
+
+    features.extend(dft_magnitudes(values, GBDT_FFT_BINS));
+    features
+}
+
+/// Slide a window over `grades`' chronological values, turning each point
+/// past the first `GBDT_LAG_COUNT` into a `(features → next value)` sample.
+fn build_gbdt_samples(grades: &[Grade]) -> Vec<GbdtSample> {
+    let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
+    (GBDT_LAG_COUNT..values.len())
+        .map(|i| GbdtSample {
+            features: build_gbdt_features(&values[..i], grades[i - 1].weight),
+            target: values[i],
+        })
+        .collect()
+}
+
+fn gbdt_split_sse(indices: &[usize], residuals: &[f64]) -> f64 {
+    let mean = indices.iter().map(|&i| residuals[i]).sum::<f64>() / indices.len() as f64;
+    indices.iter().map(|&i| (residuals[i] - mean).powi(2)).sum()
+}
+
+/// Greedily grow a shallow regression tree (bounded by `depth`) over
+/// `residuals`, splitting on whichever single feature/threshold most
+/// reduces squared error.
+fn build_gbdt_node(samples: &[GbdtSample], residuals: &[f64], indices: &[usize], depth: usize) -> GbdtNode {
+    let leaf_value = indices.iter().map(|&i| residuals[i]).sum::<f64>() / indices.len() as f64;
+    if depth == 0 || indices.len() < 2 {
+        return GbdtNode::Leaf(leaf_value);
+    }
+
+    let num_features = samples[0].features.len();
+    let mut best: Option<(usize, f64, f64)> = None;
+
+    for feature in 0..num_features {
+        let mut thresholds: Vec<f64> = indices.iter().map(|&i| samples[i].features[feature]).collect();
+        thresholds.sort_by(f64::total_cmp);
+        thresholds.dedup();
+
+        for window in thresholds.windows(2) {
+            let threshold = (window[0] + window[1]) / 2.0;
+            let (left, right): (Vec<usize>, Vec<usize>) = indices
+                .iter()
+                .copied()
+                .partition(|&i| samples[i].features[feature] <= threshold);
+            if left.is_empty() || right.is_empty() {
+                continue;
+            }
+            let sse = gbdt_split_sse(&left, residuals) + gbdt_split_sse(&right, residuals);
+            if best.map(|(_, _, best_sse)| sse < best_sse).unwrap_or(true) {
+                best = Some((feature, threshold, sse));
+            }
+        }
+    }
+
+    match best {
+        Some((feature, threshold, _)) => {
+            let (left, right): (Vec<usize>, Vec<usize>) = indices
+                .iter()
+                .copied()
+                .partition(|&i| samples[i].features[feature] <= threshold);
+            GbdtNode::Split {
+                feature,
+                threshold,
+                left: Box::new(build_gbdt_node(samples, residuals, &left, depth - 1)),
+                right: Box::new(build_gbdt_node(samples, residuals, &right, depth - 1)),
+            }
+        }
+        None => GbdtNode::Leaf(leaf_value),
+    }
+}
+
+fn predict_gbdt_tree(node: &GbdtNode, features: &[f64]) -> f64 {
+    match node {
+        GbdtNode::Leaf(value) => *value,
+        GbdtNode::Split { feature, threshold, left, right } => {
+            if features[*feature] <= *threshold {
+                predict_gbdt_tree(left, features)
+            } else {
+                predict_gbdt_tree(right, features)
+            }
+        }
+    }
+}
+
+/// Fit a small gradient-boosted ensemble (shallow trees, modest learning
+/// rate, bounded tree count) over `samples`, returning the base prediction
+/// and the sequence of boosted trees.
+fn fit_gbdt(samples: &[GbdtSample]) -> (f64, Vec<GbdtNode>) {
+    let base = samples.iter().map(|s| s.target).sum::<f64>() / samples.len() as f64;
+    let mut predictions = vec![base; samples.len()];
+    let mut trees = Vec::with_capacity(GBDT_NUM_TREES);
+
+    for _ in 0..GBDT_NUM_TREES {
+        let residuals: Vec<f64> = samples.iter().zip(&predictions).map(|(s, p)| s.target - p).collect();
+        let tree = build_gbdt_node(samples, &residuals, &(0..samples.len()).collect::<Vec<_>>(), GBDT_MAX_DEPTH);
+        for (prediction, sample) in predictions.iter_mut().zip(samples) {
+            *prediction += GBDT_LEARNING_RATE * predict_gbdt_tree(&tree, &sample.features);
+        }
+        trees.push(tree);
+    }
+
+    (base, trees)
+}
+
+fn predict_gbdt(base: f64, trees: &[GbdtNode], features: &[f64]) -> f64 {
+    base + trees
+        .iter()
+        .map(|tree| GBDT_LEARNING_RATE * predict_gbdt_tree(tree, features))
+        .sum::<f64>()
+}
+
+/// Predict the next grade with a gradient-boosted decision-tree regressor
+/// over lag, rolling-statistics, and spectral features — capturing
+/// nonlinear and seasonal structure (e.g. grades dipping before a break)
+/// the linear/EMA/isotonic methods can't express. Falls back to the
+/// standard ensemble when there isn't enough history (`GBDT_MIN_POINTS`)
+/// to fit the engineered features reliably.
+pub fn predict_from_gbdt(grades: &[Grade]) -> PredictionResult {
+    let mut sorted_grades = grades.to_vec();
+    sorted_grades.sort_by_key(|g| g.timestamp);
+
+    if sorted_grades.len() < GBDT_MIN_POINTS {
+        return predict_next_grade(&sorted_grades);
+    }
+
+    let samples = build_gbdt_samples(&sorted_grades);
+    let holdout = (samples.len() / 5).max(1).min(samples.len() - 1);
+    let train_end = samples.len() - holdout;
+    let (train_samples, test_samples) = samples.split_at(train_end);
+
+    let (held_out_base, held_out_trees) = fit_gbdt(train_samples);
+    let residuals: Vec<f64> = test_samples
+        .iter()
+        .map(|s| s.target - predict_gbdt(held_out_base, &held_out_trees, &s.features))
+        .collect();
+    let residual_mean = residuals.iter().sum::<f64>() / residuals.len() as f64;
+    let residual_std = (residuals.iter().map(|r| (r - residual_mean).powi(2)).sum::<f64>()
+        / residuals.len() as f64)
+        .sqrt();
+
+    let (base, trees) = fit_gbdt(&samples);
+    let values: Vec<f64> = sorted_grades.iter().map(|g| g.value).collect();
+    let latest_weight = sorted_grades.last().map(|g| g.weight).unwrap_or(1.0);
+    let latest_features = build_gbdt_features(&values, latest_weight);
+    let predicted_value = predict_gbdt(base, &trees, &latest_features).clamp(1.0, 10.0);
+
+    let confidence = (1.0 - (residual_std / 10.0).min(0.9)).max(0.1);
+
+    PredictionResult {
+        predicted_value,
+        confidence,
+        lower_bound: (predicted_value - 1.96 * residual_std).max(1.0),
+        upper_bound: (predicted_value + 1.96 * residual_std).min(10.0),
+        method: "gbdt".to_string(),
     }
 }
 
@@ -391,32 +1072,324 @@ pub fn calculate_pass_probability(grades: &[Grade], remaining_weight: f64) -> f6
     if grades.is_empty() {
         return 0.5; // Unknown
     }
-    
+    if remaining_weight <= 0.0 {
+        let current_weight: f64 = grades.iter().map(|g| g.weight).sum();
+        let current_sum: f64 = grades.iter().map(|g| g.value * g.weight).sum();
+        return if current_sum / current_weight >= 5.5 { 1.0 } else { 0.0 };
+    }
+
+    simulate_pass_probability(
+        grades,
+        remaining_weight,
+        1,
+        5.5,
+        DEFAULT_PASS_SIMULATION_TRIALS,
+        DEFAULT_PASS_SIMULATION_SEED,
+    )
+    .pass_fraction
+}
+
+/// Default number of Monte Carlo trials `calculate_pass_probability` runs.
+const DEFAULT_PASS_SIMULATION_TRIALS: usize = 10_000;
+
+/// Fixed seed used by `calculate_pass_probability` so repeated calls on the
+/// same history are reproducible.
+const DEFAULT_PASS_SIMULATION_SEED: u64 = 0x9a55_ce55_9a55_ce55;
+
+/// Simulate `trials` forward trajectories of `num_assessments` future grades
+/// splitting `remaining_weight` evenly across them, drawing each future
+/// grade by bootstrapping from the student's own grade history (or, when
+/// there's too little history to bootstrap from, sampling
+/// `Normal(mean, std_dev)`), clamped to `[1, 10]`. Tallies the fraction of
+/// trials whose resulting weighted average reaches `pass_threshold` and
+/// reports it with a Wilson score confidence interval.
+pub fn simulate_pass_probability(
+    grades: &[Grade],
+    remaining_weight: f64,
+    num_assessments: usize,
+    pass_threshold: f64,
+    trials: usize,
+    seed: u64,
+) -> PassProbabilityResult {
+    if grades.is_empty() || num_assessments == 0 || remaining_weight <= 0.0 || trials == 0 {
+        return PassProbabilityResult {
+            pass_fraction: 0.5,
+            ci_low: 0.0,
+            ci_high: 1.0,
+            trials: 0,
+        };
+    }
+
     let current_weight: f64 = grades.iter().map(|g| g.weight).sum();
     let current_sum: f64 = grades.iter().map(|g| g.value * g.weight).sum();
-    let current_avg = current_sum / current_weight;
-    
-    // Calculate minimum grade needed to pass (5.5 average)
     let total_weight = current_weight + remaining_weight;
-    let min_needed = (5.5 * total_weight - current_sum) / remaining_weight;
-    
-    if min_needed <= 1.0 {
-        return 1.0; // Already guaranteed to pass
+    let per_assessment_weight = remaining_weight / num_assessments as f64;
+
+    let values: Vec<f64> = grades.iter().map(|g| g.value).collect();
+    let mean = statistics::calculate_mean(&values);
+    let std_dev = statistics::calculate_std_deviation(&values).max(0.5);
+    let bootstrap_from_history = values.len() >= 5;
+
+    let mut rng = statistics::Rng::new(seed);
+    let mut passes = 0usize;
+
+    for _ in 0..trials {
+        let mut future_sum = 0.0;
+        for _ in 0..num_assessments {
+            let draw = if bootstrap_from_history {
+                values[rng.gen_index(values.len())]
+            } else {
+                mean + rng.next_gaussian() * std_dev
+            };
+            future_sum += draw.clamp(1.0, 10.0) * per_assessment_weight;
+        }
+
+        let trial_average = (current_sum + future_sum) / total_weight;
+        if trial_average >= pass_threshold {
+            passes += 1;
+        }
+    }
+
+    let pass_fraction = passes as f64 / trials as f64;
+    let (ci_low, ci_high) = wilson_score_interval(passes, trials);
+
+    PassProbabilityResult {
+        pass_fraction,
+        ci_low,
+        ci_high,
+        trials,
     }
-    if min_needed > 10.0 {
-        return 0.0; // Impossible to pass
+}
+
+/// Wilson score confidence interval for a binomial proportion — more
+/// reliable than a normal approximation when the observed proportion sits
+/// near 0 or 1, which a near-certain pass/fail forecast often does.
+fn wilson_score_interval(successes: usize, trials: usize) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = trials as f64;
+    let p_hat = successes as f64 / n;
+    const Z: f64 = 1.959963984540054; // 95% two-sided normal quantile
+
+    let denominator = 1.0 + Z * Z / n;
+    let center = p_hat + Z * Z / (2.0 * n);
+    let margin = Z * ((p_hat * (1.0 - p_hat) + Z * Z / (4.0 * n)) / n).sqrt();
+
+    (
+        ((center - margin) / denominator).max(0.0),
+        ((center + margin) / denominator).min(1.0),
+    )
+}
+
+/// Floor (in days) that a subject's stability resets toward after a
+/// failing grade.
+const STABILITY_FLOOR_DAYS: f64 = 1.0;
+
+/// Target retrievability below which a subject is considered overdue for
+/// review.
+const TARGET_RETENTION: f64 = 0.9;
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Replay a subject's grades in chronological order to estimate its
+/// current memory stability `S` (in days): a passing grade grows `S`
+/// multiplicatively, more so the further above the pass mark it is, while
+/// a failing grade resets it back down toward `STABILITY_FLOOR_DAYS`, per
+/// the classic spaced-repetition forgetting-curve model.
+fn compute_stability(grades: &[&Grade]) -> f64 {
+    let mut sorted: Vec<&Grade> = grades.to_vec();
+    sorted.sort_by_key(|g| g.timestamp);
+
+    let mut stability = STABILITY_FLOOR_DAYS;
+    for grade in sorted {
+        if grade.is_passing {
+            let growth = ((grade.value - 5.5) / 10.0).max(0.05);
+            stability *= 1.0 + growth;
+        } else {
+            stability = (stability * 0.5).max(STABILITY_FLOOR_DAYS);
+        }
+    }
+    stability
+}
+
+/// Estimate a subject's current retrievability `R(t) = exp(-t / S)` from
+/// the time elapsed since its newest grade, given `now` as an epoch
+/// timestamp in the same units as `Grade::timestamp`. Used only by
+/// `suggest_priorities`'s retention-aware scoring now; the exponential
+/// forgetting curve this was built on no longer backs a standalone
+/// schedule — see `fsrs_review_schedule` for that.
+fn current_retrievability(grades: &[&Grade], now: i64) -> (f64, f64, f64) {
+    let stability = compute_stability(grades);
+    let newest = grades.iter().map(|g| g.timestamp).max().unwrap_or(now);
+    let days_since = ((now - newest).max(0) as f64) / MS_PER_DAY;
+    let retrievability = (-days_since / stability).exp();
+    (stability, retrievability, days_since)
+}
+
+/// `DECAY`/`FACTOR` for the FSRS power-law forgetting curve
+/// `R(t) = (1 + FACTOR * t / S)^DECAY`, chosen so `R(S) = 0.9`.
+const FSRS_DECAY: f64 = -0.5;
+const FSRS_FACTOR: f64 = 19.0 / 81.0;
+
+/// Default retrievability below which a subject is due for review.
+const FSRS_TARGET_RETENTION: f64 = 0.9;
+
+const FSRS_STABILITY_FLOOR: f64 = 1.0;
+const FSRS_INITIAL_DIFFICULTY: f64 = 5.0;
+
+/// How much of the gap toward a rating's target difficulty is closed on
+/// each review.
+const FSRS_DIFFICULTY_LEARNING_RATE: f64 = 0.2;
+
+/// Fraction of stability retained after an "again" (failing) review.
+const FSRS_AGAIN_STABILITY_DECAY: f64 = 0.5;
+
+/// Tunable weights for the post-success stability growth formula.
+const FSRS_GROWTH_WEIGHT: f64 = -0.5;
+const FSRS_STABILITY_EXPONENT: f64 = 0.2;
+const FSRS_RETRIEVABILITY_SENSITIVITY: f64 = 0.1;
+
+/// FSRS review rating, derived from a grade's value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FsrsRating {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+fn fsrs_rating(value: f64) -> FsrsRating {
+    if value < 5.5 {
+        FsrsRating::Again
+    } else if value < 7.0 {
+        FsrsRating::Hard
+    } else if value < 8.5 {
+        FsrsRating::Good
+    } else {
+        FsrsRating::Easy
     }
-    
-    // Estimate probability based on how achievable the required grade is
-    // Using a sigmoid function centered around the average
-    let difficulty = (min_needed - current_avg) / 2.0;
-    1.0 / (1.0 + difficulty.exp())
+}
+
+/// Difficulty (on the `[1, 10]` scale) a rating pulls the running estimate
+/// toward.
+fn fsrs_difficulty_target(rating: FsrsRating) -> f64 {
+    match rating {
+        FsrsRating::Again => 10.0,
+        FsrsRating::Hard => 7.0,
+        FsrsRating::Good => 4.0,
+        FsrsRating::Easy => 1.0,
+    }
+}
+
+/// Retrievability `t` days after a review, given memory stability `S`.
+fn fsrs_retrievability(elapsed_days: f64, stability: f64) -> f64 {
+    (1.0 + FSRS_FACTOR * elapsed_days / stability).powf(FSRS_DECAY)
+}
+
+/// Invert `fsrs_retrievability` to find how many days until retrievability
+/// decays to `target_retention`.
+fn fsrs_days_until_retention(stability: f64, target_retention: f64) -> f64 {
+    stability * (target_retention.powf(1.0 / FSRS_DECAY) - 1.0) / FSRS_FACTOR
+}
+
+/// Fold one more review (a grade, rated from its value) into the running
+/// `(stability, difficulty)` memory state, using the elapsed time since the
+/// previous review to estimate retrievability just before this one.
+fn fsrs_update(stability: f64, difficulty: f64, elapsed_days: f64, rating: FsrsRating) -> (f64, f64) {
+    let r = if elapsed_days > 0.0 {
+        fsrs_retrievability(elapsed_days, stability)
+    } else {
+        1.0
+    };
+
+    let target_difficulty = fsrs_difficulty_target(rating);
+    let new_difficulty =
+        (difficulty + FSRS_DIFFICULTY_LEARNING_RATE * (target_difficulty - difficulty)).clamp(1.0, 10.0);
+
+    let new_stability = if rating == FsrsRating::Again {
+        (stability * FSRS_AGAIN_STABILITY_DECAY).max(FSRS_STABILITY_FLOOR)
+    } else {
+        let growth = 1.0
+            + FSRS_GROWTH_WEIGHT.exp()
+                * (11.0 - new_difficulty)
+                * stability.powf(-FSRS_STABILITY_EXPONENT)
+                * (((1.0 - r) * FSRS_RETRIEVABILITY_SENSITIVITY).exp() - 1.0);
+        (stability * growth).max(FSRS_STABILITY_FLOOR)
+    };
+
+    (new_stability, new_difficulty)
+}
+
+/// Replay a subject's grades in chronological order through the FSRS
+/// model, returning the `(stability, difficulty)` memory state as of its
+/// newest grade, plus that grade's timestamp.
+fn fsrs_replay(subject_grades: &[&Grade]) -> (f64, f64, i64) {
+    let mut sorted: Vec<&Grade> = subject_grades.to_vec();
+    sorted.sort_by_key(|g| g.timestamp);
+
+    let mut stability = FSRS_STABILITY_FLOOR;
+    let mut difficulty = FSRS_INITIAL_DIFFICULTY;
+    let mut last_timestamp = sorted.first().map(|g| g.timestamp).unwrap_or(0);
+
+    for grade in &sorted {
+        let elapsed_days = ((grade.timestamp - last_timestamp).max(0) as f64) / MS_PER_DAY;
+        let rating = fsrs_rating(grade.value);
+        let (new_stability, new_difficulty) = fsrs_update(stability, difficulty, elapsed_days, rating);
+        stability = new_stability;
+        difficulty = new_difficulty;
+        last_timestamp = grade.timestamp;
+    }
+
+    (stability, difficulty, last_timestamp)
+}
+
+/// Like `fsrs_review_schedule`, using the default target retention (0.9).
+pub fn fsrs_review_schedule_default(grades: &[Grade], now: i64) -> Vec<ReviewSchedule> {
+    fsrs_review_schedule(grades, now, FSRS_TARGET_RETENTION)
+}
+
+/// Build an FSRS-style spaced-repetition schedule, one entry per subject,
+/// sorted by soonest due (`next_review_timestamp` ascending).
+pub fn fsrs_review_schedule(grades: &[Grade], now: i64, target_retention: f64) -> Vec<ReviewSchedule> {
+    use std::collections::HashMap;
+
+    let mut subject_data: HashMap<String, Vec<&Grade>> = HashMap::new();
+    for grade in grades {
+        subject_data
+            .entry(grade.subject.to_lowercase())
+            .or_default()
+            .push(grade);
+    }
+
+    let mut schedules: Vec<ReviewSchedule> = subject_data
+        .iter()
+        .map(|(subject, subject_grades)| {
+            let (stability, difficulty, last_timestamp) = fsrs_replay(subject_grades);
+            let elapsed_days = ((now - last_timestamp).max(0) as f64) / MS_PER_DAY;
+            let current_retrievability = fsrs_retrievability(elapsed_days, stability);
+            let days_until_due = fsrs_days_until_retention(stability, target_retention);
+            let next_review_timestamp = last_timestamp + (days_until_due * MS_PER_DAY).round() as i64;
+
+            ReviewSchedule {
+                subject: subject.clone(),
+                stability,
+                difficulty,
+                current_retrievability,
+                next_review_timestamp,
+            }
+        })
+        .collect();
+
+    schedules.sort_by_key(|s| s.next_review_timestamp);
+    schedules
 }
 
 /// Suggest study priorities based on grade analysis
-pub fn suggest_priorities(grades: &[Grade]) -> Vec<(String, f64, String)> {
+pub fn suggest_priorities(grades: &[Grade], now: i64) -> Vec<(String, f64, String)> {
     use std::collections::HashMap;
-    
+
     // Group by subject
     let mut subject_data: HashMap<String, Vec<&Grade>> = HashMap::new();
     for grade in grades {
@@ -425,34 +1398,34 @@ pub fn suggest_priorities(grades: &[Grade]) -> Vec<(String, f64, String)> {
             .or_default()
             .push(grade);
     }
-    
+
     let mut priorities: Vec<(String, f64, String)> = subject_data
         .iter()
         .map(|(subject, subject_grades)| {
             let avg = subject_grades.iter().map(|g| g.value).sum::<f64>() / subject_grades.len() as f64;
-            let priority_score = calculate_priority_score(avg, subject_grades);
-            let reason = get_priority_reason(avg, subject_grades);
+            let priority_score = calculate_priority_score(avg, subject_grades, now);
+            let reason = get_priority_reason(avg, subject_grades, now);
             (subject.clone(), priority_score, reason)
         })
         .collect();
-    
+
     // Sort by priority (highest first)
     priorities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    
+
     priorities
 }
 
 /// Calculate priority score for a subject
-fn calculate_priority_score(avg: f64, grades: &[&Grade]) -> f64 {
+fn calculate_priority_score(avg: f64, grades: &[&Grade], now: i64) -> f64 {
     let mut score = 0.0;
-    
+
     // Lower average = higher priority
     score += (10.0 - avg) * 10.0;
-    
+
     // Failing grades add to priority
     let failing_count = grades.iter().filter(|g| !g.is_passing).count();
     score += failing_count as f64 * 15.0;
-    
+
     // Recent trend affects priority
     if grades.len() >= 3 {
         let recent: Vec<f64> = grades.iter().rev().take(3).map(|g| g.value).collect();
@@ -461,25 +1434,32 @@ fn calculate_priority_score(avg: f64, grades: &[&Grade]) -> f64 {
             score += 10.0; // Declining trend
         }
     }
-    
+
+    // Retention urgency: the further retrievability has decayed below the
+    // target, the more overdue the subject is for review.
+    let (_, retrievability, _) = current_retrievability(grades, now);
+    if retrievability < TARGET_RETENTION {
+        score += (TARGET_RETENTION - retrievability) * 50.0;
+    }
+
     score
 }
 
 /// Get reason for priority recommendation
-fn get_priority_reason(avg: f64, grades: &[&Grade]) -> String {
+fn get_priority_reason(avg: f64, grades: &[&Grade], now: i64) -> String {
     if avg < 5.5 {
         return "Failing average - immediate attention needed".to_string();
     }
-    
+
     let failing_count = grades.iter().filter(|g| !g.is_passing).count();
     if failing_count > 0 {
         return format!("{} failing grade(s) affecting average", failing_count);
     }
-    
+
     if avg < 6.5 {
         return "Below target average - room for improvement".to_string();
     }
-    
+
     if grades.len() >= 3 {
         let recent: Vec<f64> = grades.iter().rev().take(3).map(|g| g.value).collect();
         let recent_avg = recent.iter().sum::<f64>() / recent.len() as f64;
@@ -487,7 +1467,12 @@ fn get_priority_reason(avg: f64, grades: &[&Grade]) -> String {
             return "Recent decline detected".to_string();
         }
     }
-    
+
+    let (_, retrievability, _) = current_retrievability(grades, now);
+    if retrievability < TARGET_RETENTION {
+        return "retention decayed, review overdue".to_string();
+    }
+
     "Maintain current performance".to_string()
 }
 
@@ -520,6 +1505,25 @@ mod tests {
         assert!(prediction.confidence >= 0.0 && prediction.confidence <= 1.0);
     }
 
+    #[test]
+    fn test_prediction_interval_widens_for_higher_confidence_vs_lower() {
+        let grades = create_test_grades();
+        let narrow = predict_from_trend_with_confidence(&grades, 0.80);
+        let wide = predict_from_trend_with_confidence(&grades, 0.99);
+
+        let narrow_width = narrow.upper_bound - narrow.lower_bound;
+        let wide_width = wide.upper_bound - wide.lower_bound;
+        assert!(wide_width >= narrow_width);
+    }
+
+    #[test]
+    fn test_prediction_interval_bounds_stay_within_grade_range() {
+        let grades = create_test_grades();
+        let prediction = predict_from_regression_with_confidence(&grades, 0.95);
+        assert!(prediction.lower_bound >= 1.0 && prediction.lower_bound <= prediction.upper_bound);
+        assert!(prediction.upper_bound <= 10.0);
+    }
+
     #[test]
     fn test_whatif() {
         let grades = create_test_grades();
@@ -531,6 +1535,26 @@ mod tests {
         assert!(result.new_average > result.current_average); // 9.0 should increase average
     }
 
+    #[test]
+    fn test_backtest_methods_weights_sum_to_one() {
+        let grades = create_test_grades();
+        let report = backtest_methods(&grades);
+        assert_eq!(report.folds, 2);
+        let total = report.trend_weight
+            + report.ema_weight
+            + report.regression_weight
+            + report.isotonic_weight;
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_backtest_methods_no_folds_for_short_history() {
+        let grades = vec![Grade::new(7.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000)];
+        let report = backtest_methods(&grades);
+        assert_eq!(report.folds, 0);
+        assert_eq!(report.trend_weight, 0.0);
+    }
+
     #[test]
     fn test_pass_probability() {
         let passing_grades = vec![
@@ -539,4 +1563,229 @@ mod tests {
         let probability = calculate_pass_probability(&passing_grades, 1.0);
         assert!(probability > 0.5); // Good grades should have high pass probability
     }
+
+    #[test]
+    fn test_simulate_pass_probability_is_reproducible_for_same_seed() {
+        let grades = create_test_grades();
+        let a = simulate_pass_probability(&grades, 2.0, 1, 5.5, 2000, 42);
+        let b = simulate_pass_probability(&grades, 2.0, 1, 5.5, 2000, 42);
+        assert_eq!(a.pass_fraction, b.pass_fraction);
+        assert_eq!(a.ci_low, b.ci_low);
+        assert_eq!(a.ci_high, b.ci_high);
+    }
+
+    #[test]
+    fn test_simulate_pass_probability_ci_contains_point_estimate() {
+        let grades = create_test_grades();
+        let result = simulate_pass_probability(&grades, 2.0, 1, 5.5, 2000, 7);
+        assert!(result.ci_low <= result.pass_fraction && result.pass_fraction <= result.ci_high);
+    }
+
+    #[test]
+    fn test_predict_from_isotonic_tracks_steady_improvement() {
+        let grades = vec![
+            Grade::new(5.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(6.0, 1.0, "Math".to_string(), "Test 2".to_string(), 2000),
+            Grade::new(5.5, 1.0, "Math".to_string(), "Test 3".to_string(), 3000), // noisy dip
+            Grade::new(7.0, 1.0, "Math".to_string(), "Test 4".to_string(), 4000),
+        ];
+        let prediction = predict_from_isotonic(&grades);
+        assert!(prediction.predicted_value >= 7.0);
+    }
+
+    #[test]
+    fn test_pool_adjacent_violators_merges_violating_blocks() {
+        let values = vec![(5.0, 1.0), (6.0, 1.0), (4.0, 1.0)];
+        let blocks = pool_adjacent_violators(&values, true);
+        // The 4.0 violates non-decreasing order against 6.0, so those two
+        // pool into a 5.0 block; that ties the leading 5.0 rather than
+        // violating it, so two blocks remain, both at 5.0.
+        assert_eq!(blocks.len(), 2);
+        assert!((blocks[0].level - 5.0).abs() < 1e-9);
+        assert!((blocks[1].level - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pool_adjacent_violators_leaves_monotone_input_untouched() {
+        let values = vec![(1.0, 1.0), (2.0, 1.0), (3.0, 1.0)];
+        let blocks = pool_adjacent_violators(&values, true);
+        assert_eq!(blocks.len(), 3);
+    }
+
+    #[test]
+    fn test_calibrate_parameters_improves_or_matches_default_loss() {
+        let grades = vec![
+            Grade::new(5.0, 1.0, "Math".to_string(), "Test 1".to_string(), 1000),
+            Grade::new(5.5, 1.0, "Math".to_string(), "Test 2".to_string(), 2000),
+            Grade::new(6.0, 1.0, "Math".to_string(), "Test 3".to_string(), 3000),
+            Grade::new(6.5, 1.0, "Math".to_string(), "Test 4".to_string(), 4000),
+            Grade::new(7.0, 1.0, "Math".to_string(), "Test 5".to_string(), 5000),
+        ];
+        let config = GradientDescentConfig::default();
+        let result = calibrate_parameters(&grades, &config);
+
+        assert!(result.alpha > 0.0 && result.alpha < 1.0);
+        assert!(result.regression_exponent > 0.0);
+        assert!(!result.loss_curve.is_empty());
+        assert!(*result.loss_curve.last().unwrap() <= result.loss_curve[0] + 1e-9);
+    }
+
+    #[test]
+    fn test_calibrate_parameters_short_history_returns_defaults() {
+        let grades = create_test_grades()[..2].to_vec();
+        let config = GradientDescentConfig::default();
+        let result = calibrate_parameters(&grades, &config);
+
+        assert_eq!(result.iterations, 0);
+        assert_eq!(result.alpha, DEFAULT_EMA_ALPHA);
+        assert_eq!(result.regression_exponent, DEFAULT_REGRESSION_EXPONENT);
+    }
+
+    #[test]
+    fn test_wilson_score_interval_narrows_with_more_trials() {
+        let (narrow_low, narrow_high) = wilson_score_interval(9000, 10_000);
+        let (wide_low, wide_high) = wilson_score_interval(9, 10);
+        assert!((narrow_high - narrow_low) < (wide_high - wide_low));
+    }
+
+    #[test]
+    fn test_compute_stability_grows_after_consecutive_passing_grades() {
+        let grades = vec![
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(8.5, 1.0, "Math".to_string(), "Test 2".to_string(), 1),
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 3".to_string(), 2),
+        ];
+        let refs: Vec<&Grade> = grades.iter().collect();
+
+        let stability = compute_stability(&refs);
+
+        assert!(stability > STABILITY_FLOOR_DAYS);
+    }
+
+    #[test]
+    fn test_compute_stability_resets_toward_floor_after_failing_grade() {
+        let grades = vec![
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 2".to_string(), 1),
+            Grade::new(3.0, 1.0, "Math".to_string(), "Test 3".to_string(), 2),
+        ];
+        let refs: Vec<&Grade> = grades.iter().collect();
+
+        let before_failure = {
+            let passing_only: Vec<&Grade> = refs[..2].to_vec();
+            compute_stability(&passing_only)
+        };
+        let after_failure = compute_stability(&refs);
+
+        assert!(after_failure < before_failure);
+    }
+
+    #[test]
+    fn test_suggest_priorities_raises_score_for_overdue_retention() {
+        let day = MS_PER_DAY as i64;
+        let stale_grades = vec![Grade::new(8.0, 1.0, "History".to_string(), "Test 1".to_string(), 0)];
+        let fresh_grades = vec![Grade::new(8.0, 1.0, "History".to_string(), "Test 1".to_string(), 500 * day)];
+
+        let now = 500 * day;
+        let stale_score = calculate_priority_score(8.0, &stale_grades.iter().collect::<Vec<_>>(), now);
+        let fresh_score = calculate_priority_score(8.0, &fresh_grades.iter().collect::<Vec<_>>(), now);
+
+        assert!(stale_score > fresh_score);
+    }
+
+    #[test]
+    fn test_get_priority_reason_explains_retention_decay() {
+        let day = MS_PER_DAY as i64;
+        let grades = vec![Grade::new(8.0, 1.0, "History".to_string(), "Test 1".to_string(), 0)];
+        let refs: Vec<&Grade> = grades.iter().collect();
+
+        let reason = get_priority_reason(8.0, &refs, 500 * day);
+
+        assert_eq!(reason, "retention decayed, review overdue");
+    }
+
+    #[test]
+    fn test_predict_from_gbdt_falls_back_below_min_points() {
+        let grades: Vec<Grade> = (0..5)
+            .map(|i| Grade::new(7.0, 1.0, "Math".to_string(), format!("Test {}", i), i as i64 * 1000))
+            .collect();
+
+        let result = predict_from_gbdt(&grades);
+
+        assert!(result.predicted_value >= 1.0 && result.predicted_value <= 10.0);
+    }
+
+    #[test]
+    fn test_predict_from_gbdt_tracks_stable_series() {
+        let grades: Vec<Grade> = (0..20)
+            .map(|i| Grade::new(7.0, 1.0, "Math".to_string(), format!("Test {}", i), i as i64 * 1000))
+            .collect();
+
+        let result = predict_from_gbdt(&grades);
+
+        assert_eq!(result.method, "gbdt");
+        assert!((result.predicted_value - 7.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_dft_magnitudes_flags_periodic_signal_over_flat_one() {
+        let period = 32;
+        let periodic: Vec<f64> = (0..32)
+            .map(|i| if i % period < period / 2 { 8.0 } else { 6.0 })
+            .collect();
+        let flat = vec![7.0; 32];
+
+        let periodic_mags = dft_magnitudes(&periodic, 3);
+        let flat_mags = dft_magnitudes(&flat, 3);
+
+        assert!(periodic_mags[1] > flat_mags[1]);
+    }
+
+    #[test]
+    fn test_fsrs_stability_grows_after_consecutive_good_reviews() {
+        let day = MS_PER_DAY as i64;
+        let grades = vec![
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 2".to_string(), 5 * day),
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 3".to_string(), 10 * day),
+        ];
+        let refs: Vec<&Grade> = grades.iter().collect();
+
+        let (stability, _, _) = fsrs_replay(&refs);
+
+        assert!(stability > FSRS_STABILITY_FLOOR);
+    }
+
+    #[test]
+    fn test_fsrs_stability_resets_after_again_review() {
+        let day = MS_PER_DAY as i64;
+        let grades = vec![
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(8.0, 1.0, "Math".to_string(), "Test 2".to_string(), 5 * day),
+            Grade::new(3.0, 1.0, "Math".to_string(), "Test 3".to_string(), 10 * day),
+        ];
+        let refs: Vec<&Grade> = grades.iter().collect();
+
+        let before_failure_refs: Vec<&Grade> = refs[..2].to_vec();
+        let (stability_before, _, _) = fsrs_replay(&before_failure_refs);
+        let (stability_after, _, _) = fsrs_replay(&refs);
+
+        assert!(stability_after < stability_before);
+    }
+
+    #[test]
+    fn test_fsrs_review_schedule_sorted_by_soonest_due() {
+        let day = MS_PER_DAY as i64;
+        let grades = vec![
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 1".to_string(), 0),
+            Grade::new(9.0, 1.0, "Math".to_string(), "Test 2".to_string(), 5 * day),
+            Grade::new(3.0, 1.0, "Gym".to_string(), "Test 1".to_string(), 0),
+        ];
+
+        let schedule = fsrs_review_schedule_default(&grades, 5 * day);
+
+        assert_eq!(schedule.len(), 2);
+        assert!(schedule[0].next_review_timestamp <= schedule[1].next_review_timestamp);
+        assert_eq!(schedule[0].subject, "gym");
+    }
 }